@@ -1,15 +1,18 @@
 pub mod action;
 pub mod body;
-pub mod bounding_box;
 pub mod cameras;
 pub mod crop;
 pub mod damage;
+pub mod floor;
 pub mod ground;
 pub mod health;
 pub mod inventory;
 pub mod item;
+pub mod map_view;
+pub mod moisture;
 pub mod movement;
 pub mod name;
+pub mod note;
 pub mod player;
 pub mod spawns;
 pub mod structure;
@@ -31,8 +31,11 @@ pub struct PickupAction {
 pub enum InteractAction {
     PlantCrop(PlantCropAction),
     DropFloors,
+    ClimbFloors,
     DigAction(DigAction),
     ClearAction(ClearAction),
+    WaterAction(WaterAction),
+    Harvest(HarvestAction),
 }
 
 #[derive(Debug, PartialEq)]
@@ -49,3 +52,13 @@ pub struct DigAction {
 pub struct ClearAction {
     pub entity: Entity,
 }
+
+#[derive(Debug, PartialEq)]
+pub struct WaterAction {
+    pub entity: Entity,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HarvestAction {
+    pub entity: Entity,
+}
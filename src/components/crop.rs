@@ -3,24 +3,142 @@ use bevy::{
     prelude::{Bundle, Component, SpriteSheetBundle, Transform},
     sprite::TextureAtlasSprite,
 };
+use rand::Rng;
 
-use crate::{configuration::crops::CropConfiguration, sprites::Sprites};
+use crate::{
+    configuration::{crops::CropConfiguration, floors::SoilConfig},
+    sprites::Sprites,
+};
+
+use super::{moisture::SoilMoisture, name::Name};
 
-use super::name::Name;
+// Growing slower than this counts as neglect, the same as going without water
+const POOR_SOIL_GROWTH_RATE: u32 = 25;
+const PEST_CHANCE_SCALE: u32 = 10_000;
 
 #[derive(Component)]
 pub struct Crop {
     pub current_stage_index: usize,
     pub config: CropConfiguration,
+    pub growth: u32,
+    pub neglected_ticks: u32,
+    pub withered: bool,
+    pub withered_ticks: u32,
+}
+
+// What the tile under a crop gives it for one world tick, both in percent
+#[derive(Clone, Copy)]
+pub struct GrowingConditions {
+    pub moisture: u32,
+    pub growth_rate: u32,
+}
+
+impl Crop {
+    pub fn is_mature(&self) -> bool {
+        self.current_stage_index + 1 >= self.config.stages.len()
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.withered && self.withered_ticks >= self.config.withered.ticks_until_dead()
+    }
+
+    pub fn sprite_index(&self, stages: &CropStages) -> Option<usize> {
+        if self.withered {
+            return self.config.withered.sprite_index;
+        }
+
+        stages
+            .stages
+            .get(self.current_stage_index)
+            .map(|stage| stage.sprite_index)
+    }
+
+    // One world tick of growth, true when the crop's sprite has to change
+    pub fn grow(
+        &mut self,
+        stages: &mut CropStages,
+        conditions: GrowingConditions,
+        rng: &mut impl Rng,
+    ) -> bool {
+        // Withered crops only wait to die off
+        if self.withered {
+            self.withered_ticks += 1;
+            return false;
+        }
+
+        // Mature crops stay in place until they are harvested
+        if self.is_mature() {
+            return false;
+        }
+
+        let stage = match stages.stages.get_mut(self.current_stage_index) {
+            Some(it) => it,
+            _ => {
+                println!("No stage!");
+                return false;
+            }
+        };
+
+        if rng.gen_range(0..PEST_CHANCE_SCALE) < self.config.pest_chance {
+            self.withered = true;
+            return true;
+        }
+
+        if conditions.moisture < stage.moisture_needed
+            || conditions.growth_rate < POOR_SOIL_GROWTH_RATE
+        {
+            self.neglected_ticks += 1;
+            if self.neglected_ticks >= stage.ticks_until_withered {
+                self.withered = true;
+                return true;
+            }
+        } else {
+            self.neglected_ticks = 0;
+        }
+
+        // Growth builds up a tick in the stage for every hundred percent of growth rate
+        self.growth += conditions.growth_rate;
+        stage.ticks_in_stage += self.growth / 100;
+        self.growth %= 100;
+        if stage.ticks_in_stage < stage.min_ticks_in_stage {
+            return false;
+        }
+
+        let chance_to_grow: u32 = rng.gen_range(1..100);
+
+        if chance_to_grow > stage.chance_to_advance(conditions.moisture) {
+            return false;
+        }
+
+        if stages.stages.get(self.current_stage_index + 1).is_none() {
+            return false;
+        }
+
+        self.current_stage_index += 1;
+        true
+    }
 }
 
 pub struct CropStage {
     pub ticks_in_stage: u32,
     pub min_ticks_in_stage: u32,
     pub chance_to_advance: u32,
+    pub moisture_needed: u32,
+    pub ticks_until_withered: u32,
     pub sprite_index: usize,
 }
 
+impl CropStage {
+    // Soil drier than the stage needs scales its chance down in proportion
+    fn chance_to_advance(&self, moisture: u32) -> u32 {
+        if moisture >= self.moisture_needed {
+            return self.chance_to_advance;
+        }
+
+        self.chance_to_advance * moisture / self.moisture_needed
+    }
+}
+
 #[derive(Component)]
 pub struct CropStages {
     pub stages: Vec<CropStage>,
@@ -48,14 +166,17 @@ impl CropBundle {
         config: &CropConfiguration,
         crop_sprite_scale: f32,
         sprite_scale: f32,
+        rng: &mut impl Rng,
     ) -> Self {
         let stages: Vec<CropStage> = config
             .stages
             .iter()
             .map(|stage| CropStage {
                 ticks_in_stage: 0,
-                min_ticks_in_stage: stage.configured_ticks_in_stage(),
+                min_ticks_in_stage: stage.configured_ticks_in_stage(rng),
                 chance_to_advance: stage.chance_to_advance(),
+                moisture_needed: stage.moisture_needed(),
+                ticks_until_withered: stage.ticks_until_withered(),
                 sprite_index: stage.sprite_index.unwrap(),
             })
             .collect();
@@ -75,7 +196,39 @@ impl CropBundle {
             crop: Crop {
                 config: config.clone(),
                 current_stage_index: 0,
+                growth: 0,
+                neglected_ticks: 0,
+                withered: false,
+                withered_ticks: 0,
             },
         }
     }
+
+    // Grows the crop through the ticks that passed while its floor was unloaded, with the soil
+    // under it drying out alongside. Crops that died in the meantime come back dead
+    pub fn catch_up(
+        &mut self,
+        ticks: u64,
+        mut moisture: SoilMoisture,
+        soil: &SoilConfig,
+        rng: &mut impl Rng,
+    ) {
+        let growth_rate = self.crop.config.growth_rate(soil);
+        for _ in 0..ticks {
+            if self.crop.is_mature() || self.crop.is_dead() {
+                break;
+            }
+
+            moisture.drain(1, soil.drainage);
+            let conditions = GrowingConditions {
+                moisture: moisture.percent(),
+                growth_rate,
+            };
+            self.crop.grow(&mut self.stages, conditions, rng);
+        }
+
+        if let Some(sprite_index) = self.crop.sprite_index(&self.stages) {
+            self.sprite.sprite.index = sprite_index;
+        }
+    }
 }
@@ -7,12 +7,11 @@ pub struct Damage {
 }
 
 impl Damage {
-    pub fn damage_dealt(&self) -> i32 {
+    pub fn damage_dealt(&self, rng: &mut impl Rng) -> i32 {
         if self.min_damage == self.max_damage {
             return self.max_damage;
         }
 
-        let mut rng = rand::thread_rng();
         rng.gen_range(self.min_damage..self.max_damage)
     }
 }
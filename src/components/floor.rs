@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+// The level of the floor an entity belongs to, so it can be stashed when the player leaves
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FloorLevel(pub usize);
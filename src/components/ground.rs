@@ -5,14 +5,20 @@ use bevy::{
     utils::default,
 };
 
-use crate::{configuration::floors::FloorConfig, sprites::Sprites};
+use crate::{
+    configuration::floors::{FloorConfig, SoilConfig},
+    sprites::Sprites,
+};
 
 use super::body::Body;
 
 use rand::Rng;
 
 #[derive(Component)]
-pub struct GroundTile;
+pub struct GroundTile {
+    pub tillable: bool,
+    pub soil: SoilConfig,
+}
 
 #[derive(Bundle)]
 pub struct GroundTileBundle {
@@ -29,9 +35,8 @@ impl GroundTileBundle {
         sprites: &Sprites,
         floor_config: &FloorConfig,
         sprite_scale: f32,
-        tile_size: f32,
+        rng: &mut impl Rng,
     ) -> Self {
-        let mut rng = rand::thread_rng();
         let num_options = floor_config.sprite_options.len();
         let random_index: usize = rng.gen_range(0..num_options);
         let floor_index = floor_config
@@ -41,10 +46,11 @@ impl GroundTileBundle {
             .sprite_index
             .unwrap();
         Self {
-            tile_type: GroundTile,
+            tile_type: GroundTile {
+                tillable: floor_config.tillable,
+                soil: floor_config.soil,
+            },
             collide: Body {
-                tile_size,
-                cell_center: position,
                 underground: false,
                 visibility_before_inventory: false,
             },
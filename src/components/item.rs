@@ -1,15 +1,97 @@
+use std::str::FromStr;
+
 use bevy::{
     math::Vec3,
     prelude::{Bundle, Component, SpriteSheetBundle, Transform, Visibility},
     sprite::TextureAtlasSprite,
 };
 
-use crate::{configuration::tools::ToolConfiguration, sprites::Sprites};
+use crate::{
+    configuration::{items::ItemConfiguration, notes::NoteConfiguration, tools::ToolConfiguration},
+    sprites::Sprites,
+};
 
 use super::body::Body;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemKind {
+    Seed,
+    Produce,
+    Material,
+    Consumable,
+}
+
+#[derive(Debug)]
+pub struct ParseItemKindError;
+
+impl FromStr for ItemKind {
+    type Err = ParseItemKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "seed" => Ok(Self::Seed),
+            "produce" => Ok(Self::Produce),
+            "material" => Ok(Self::Material),
+            "consumable" => Ok(Self::Consumable),
+            _ => Err(ParseItemKindError),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    pub fn weight(&self) -> u32 {
+        match self {
+            Self::Common => 70,
+            Self::Uncommon => 25,
+            Self::Rare => 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseRarityError;
+
+impl FromStr for Rarity {
+    type Err = ParseRarityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "common" => Ok(Self::Common),
+            "uncommon" => Ok(Self::Uncommon),
+            "rare" => Ok(Self::Rare),
+            _ => Err(ParseRarityError),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ItemStack {
+    pub config: ItemConfiguration,
+    pub quantity: u32,
+}
+
 pub enum ItemType {
     Tool(ToolConfiguration),
+    Stack(ItemStack),
+    Note(NoteConfiguration),
+}
+
+impl ItemType {
+    pub fn key(&self) -> &String {
+        match self {
+            Self::Tool(tool) => tool.key(),
+            Self::Stack(stack) => stack.config.key(),
+            Self::Note(note) => &note.key,
+        }
+    }
 }
 
 #[derive(Component)]
@@ -32,15 +114,12 @@ impl ItemBundle {
         sprites: &Sprites,
         item_index: usize,
         sprite_scale: f32,
-        tile_size: f32,
         underground: bool,
         item_type: ItemType,
     ) -> Self {
         Self {
             body: Body {
-                tile_size,
                 underground,
-                cell_center: position,
                 visibility_before_inventory: false,
             },
             sprite: SpriteSheetBundle {
@@ -51,7 +130,9 @@ impl ItemBundle {
                 },
                 sprite: TextureAtlasSprite::new(item_index),
                 texture_atlas: sprites.atlas_handle.clone(),
-                visibility: Visibility { is_visible: true },
+                visibility: Visibility {
+                    is_visible: !underground,
+                },
                 ..Default::default()
             },
             item: Item { item_type },
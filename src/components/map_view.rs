@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct Minimap;
+
+#[derive(Component)]
+pub struct FullMap;
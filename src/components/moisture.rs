@@ -0,0 +1,28 @@
+use bevy::prelude::Component;
+
+// Soil loses its drainage in moisture every world tick, so a full watering lasts 500 ticks on
+// soil that drains at the normal rate
+pub const MAX_MOISTURE: u32 = 50_000;
+
+// How wet a tilled tile is, crops planted on it read it through the tile occupancy
+#[derive(Component, Clone, Copy, Default)]
+pub struct SoilMoisture {
+    pub level: u32,
+}
+
+impl SoilMoisture {
+    pub fn percent(&self) -> u32 {
+        self.level * 100 / MAX_MOISTURE
+    }
+
+    pub fn water(&mut self, percent: u32) {
+        self.level = (self.level + percent * MAX_MOISTURE / 100).min(MAX_MOISTURE);
+    }
+
+    pub fn drain(&mut self, ticks: u64, drainage: u32) {
+        let drained = ticks
+            .saturating_mul(drainage as u64)
+            .min(MAX_MOISTURE as u64);
+        self.level = self.level.saturating_sub(drained as u32);
+    }
+}
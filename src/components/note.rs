@@ -0,0 +1,50 @@
+use bevy::{
+    prelude::{Bundle, Color, Component, Handle, TextBundle},
+    text::{Font, Text, TextStyle},
+    ui::{AlignSelf, PositionType, Style, UiRect, Val},
+};
+
+#[derive(Default, Component)]
+pub struct NoteReader {
+    pub index: usize,
+}
+
+#[derive(Component)]
+pub struct NoteText;
+
+#[derive(Bundle)]
+pub struct NoteTextBundle {
+    pub note_text: NoteText,
+
+    #[bundle]
+    pub text: TextBundle,
+}
+
+impl NoteTextBundle {
+    pub fn build(padding: f32, text: String, font: &Handle<Font>, font_size: f32) -> Self {
+        Self {
+            note_text: NoteText,
+            text: TextBundle {
+                style: Style {
+                    align_self: AlignSelf::FlexEnd,
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(padding),
+                        left: Val::Px(padding),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size,
+                        color: Color::WHITE,
+                    },
+                ),
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use crate::{
     configuration::{
-        crops::CropConfiguration, game::GameConfiguration, map::grid_coordinate_from_world,
+        crops::{CropConfiguration, CropsConfig},
+        game::GameConfiguration,
+        items::{ItemConfiguration, ItemsConfig},
+        map::grid_coordinate_from_world,
+        notes::NoteConfiguration,
         tools::ToolConfiguration,
     },
     sprites::Sprites,
@@ -8,6 +12,7 @@ use crate::{
 
 use super::{
     action::CurrentAction,
+    item::{ItemKind, ItemStack},
     movement::{Direction, Speed},
     name::Name,
     tool::Tool,
@@ -24,8 +29,22 @@ pub struct PlayerInventory {
     pub current_crop_config: Option<CropConfiguration>,
     pub current_tool: Option<Tool>,
     pub current_selected_index: Option<usize>,
-    pub held_seeds: Vec<CropConfiguration>,
+    pub held_seeds: Vec<HeldSeeds>,
     pub held_tools: Vec<ToolConfiguration>,
+    pub held_produce: Vec<HeldProduce>,
+    pub held_items: Vec<ItemStack>,
+    pub held_notes: Vec<NoteConfiguration>,
+}
+
+pub struct HeldSeeds {
+    pub config: CropConfiguration,
+    pub quantity: u32,
+}
+
+pub struct HeldProduce {
+    pub key: String,
+    pub name: String,
+    pub quantity: u32,
 }
 
 impl PlayerInventory {
@@ -43,9 +62,166 @@ impl PlayerInventory {
         }
     }
 
+    pub fn watering_can_equipped(&self) -> bool {
+        match &self.current_tool {
+            Some(tool) => tool.can_water(),
+            _ => false,
+        }
+    }
+
     pub fn seed_equipped(&self) -> bool {
         self.current_crop_config.is_some()
     }
+
+    pub fn add_produce(
+        &mut self,
+        config: &CropConfiguration,
+        quantity: u32,
+        items_config: &ItemsConfig,
+    ) -> u32 {
+        let stack_size = items_config.crop_stack_size(ItemKind::Produce, &config.key);
+
+        match self
+            .held_produce
+            .iter_mut()
+            .find(|produce| produce.key == config.key)
+        {
+            Some(produce) => {
+                let taken = room_in_stack(produce.quantity, quantity, stack_size);
+                produce.quantity += taken;
+                taken
+            }
+            _ => {
+                let taken = room_in_stack(0, quantity, stack_size);
+                if taken > 0 {
+                    self.held_produce.push(HeldProduce {
+                        key: config.key.clone(),
+                        name: config.name.clone(),
+                        quantity: taken,
+                    });
+                }
+                taken
+            }
+        }
+    }
+
+    pub fn add_seeds(
+        &mut self,
+        config: &CropConfiguration,
+        quantity: u32,
+        items_config: &ItemsConfig,
+    ) -> u32 {
+        let stack_size = items_config.crop_stack_size(ItemKind::Seed, &config.key);
+
+        match self
+            .held_seeds
+            .iter_mut()
+            .find(|seeds| seeds.config.key == config.key)
+        {
+            Some(seeds) => {
+                let taken = room_in_stack(seeds.quantity, quantity, stack_size);
+                seeds.quantity += taken;
+                taken
+            }
+            _ => {
+                let taken = room_in_stack(0, quantity, stack_size);
+                if taken > 0 {
+                    self.held_seeds.push(HeldSeeds {
+                        config: config.clone(),
+                        quantity: taken,
+                    });
+                }
+                taken
+            }
+        }
+    }
+
+    pub fn add_item(
+        &mut self,
+        config: &ItemConfiguration,
+        quantity: u32,
+        crops_config: &CropsConfig,
+        items_config: &ItemsConfig,
+    ) -> u32 {
+        let crop_config = config
+            .crop()
+            .and_then(|key| crops_config.config_by_key(key));
+
+        match (config.kind(), crop_config) {
+            (ItemKind::Seed, Some(crop_config)) => {
+                self.add_seeds(crop_config, quantity, items_config)
+            }
+            (ItemKind::Produce, Some(crop_config)) => {
+                self.add_produce(crop_config, quantity, items_config)
+            }
+            _ => match self
+                .held_items
+                .iter_mut()
+                .find(|stack| stack.config.key() == config.key())
+            {
+                Some(stack) => {
+                    let taken = room_in_stack(stack.quantity, quantity, config.stack_size());
+                    stack.quantity += taken;
+                    taken
+                }
+                _ => {
+                    let taken = room_in_stack(0, quantity, config.stack_size());
+                    if taken > 0 {
+                        self.held_items.push(ItemStack {
+                            config: config.clone(),
+                            quantity: taken,
+                        });
+                    }
+                    taken
+                }
+            },
+        }
+    }
+
+    pub fn add_note(&mut self, config: &NoteConfiguration) -> usize {
+        match self
+            .held_notes
+            .iter()
+            .position(|note| note.key == config.key)
+        {
+            Some(index) => index,
+            _ => {
+                self.held_notes.push(config.clone());
+                self.held_notes.len() - 1
+            }
+        }
+    }
+
+    pub fn consume_current_seed(&mut self) -> bool {
+        let key = match &self.current_crop_config {
+            Some(it) => it.key.clone(),
+            _ => return false,
+        };
+
+        let index = match self
+            .held_seeds
+            .iter()
+            .position(|seeds| seeds.config.key == key && seeds.quantity > 0)
+        {
+            Some(it) => it,
+            _ => return false,
+        };
+
+        self.held_seeds[index].quantity -= 1;
+
+        if self.held_seeds[index].quantity == 0 {
+            self.held_seeds.remove(index);
+            self.current_crop_config = None;
+            self.current_selected_index = None;
+        }
+
+        true
+    }
+}
+
+/// How many of `quantity` fit on top of `held` without going past `stack_size`
+fn room_in_stack(held: u32, quantity: u32, stack_size: u32) -> u32 {
+    quantity.min(stack_size.saturating_sub(held))
 }
 
 #[derive(Component)]
@@ -81,14 +257,6 @@ impl PlayerBundle {
         sprites: &Sprites,
         config: &GameConfiguration,
     ) -> Self {
-        let held_seeds: Vec<CropConfiguration> = config
-            .crops_config
-            .configurations
-            .iter()
-            .filter(|crop_config| crop_config.starter)
-            .cloned()
-            .collect();
-
         let held_tools: Vec<ToolConfiguration> = config
             .tool_configs
             .configurations
@@ -96,7 +264,7 @@ impl PlayerBundle {
             .filter(|tool_config| tool_config.starter())
             .cloned()
             .collect();
-        Self {
+        let mut bundle = Self {
             name: Name(config.player_config.info.name.clone()),
             coordinates: PlayerCoordinates {
                 current: Some(grid_coordinate_from_world(
@@ -124,12 +292,30 @@ impl PlayerBundle {
             player: Player,
             action: CurrentAction::default(),
             inventory: PlayerInventory {
-                held_seeds,
+                held_seeds: Vec::new(),
                 held_tools,
+                held_produce: Vec::new(),
+                held_items: Vec::new(),
+                held_notes: Vec::new(),
                 current_selected_index: None,
                 current_crop_config: None,
                 current_tool: None,
             },
+        };
+
+        for crop_config in config
+            .crops_config
+            .configurations
+            .iter()
+            .filter(|crop_config| crop_config.starter)
+        {
+            bundle.inventory.add_seeds(
+                crop_config,
+                crop_config.starter_quantity,
+                &config.items_config,
+            );
         }
+
+        bundle
     }
 }
@@ -1,6 +1,8 @@
 use bevy::prelude::Component;
 use tdlg::map::TopDownMap;
 
+use crate::save::{FloorSave, PlayerSave};
+
 use super::{crop::CropSpawn, structure::StructureSpawn};
 
 #[derive(Default, Component)]
@@ -12,4 +14,8 @@ pub struct Spawns {
 
 pub struct MapSpawn {
     pub map: TopDownMap,
+    pub restore: Option<FloorSave>,
+    pub player: Option<PlayerSave>,
+    // World ticks since the restored floor was left
+    pub elapsed_ticks: u64,
 }
@@ -21,6 +21,9 @@ pub enum StructureType {
     Wall,
     Table,
     Hole,
+    Ladder,
+    Soil,
+    Plant,
     DirtChunks,
     Unknown,
 }
@@ -36,6 +39,9 @@ impl FromStr for StructureType {
             "wall" => Ok(Self::Wall),
             "table" => Ok(Self::Table),
             "hole" => Ok(Self::Hole),
+            "ladder" => Ok(Self::Ladder),
+            "soil" => Ok(Self::Soil),
+            "plant" => Ok(Self::Plant),
             "dirt_chunks" => Ok(Self::DirtChunks),
             _ => Err(ParseStructureTypeError),
         }
@@ -52,6 +58,7 @@ impl Default for StructureType {
 pub struct Structure {
     pub health: Health,
     pub health_configs: Vec<StructureHealth>,
+    key: String,
     structure_type: StructureType,
     default_can_be_walked_on: bool,
     default_can_be_broken: bool,
@@ -132,9 +139,26 @@ impl Structure {
             .find(|config| config.matches_health(current))
     }
 
+    // Broken walls are walked over, so they stop blocking sight too
+    pub fn blocks_sight(&self) -> bool {
+        self.structure_type == StructureType::Wall && !self.can_be_walked_on()
+    }
+
     pub fn is_exit(&self) -> bool {
         self.structure_type == StructureType::Hole
     }
+
+    pub fn is_ladder(&self) -> bool {
+        self.structure_type == StructureType::Ladder
+    }
+
+    pub fn is_tilled_soil(&self) -> bool {
+        self.structure_type == StructureType::Soil
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
 }
 
 pub struct StructureSpawn {
@@ -157,7 +181,6 @@ impl StructureBundle {
         atlas_handle: &Handle<TextureAtlas>,
         structure_config: &StructureConfig,
         sprite_config: &SpriteConfig,
-        tile_size: f32,
     ) -> Self {
         let health_configs: Vec<StructureHealth> = structure_config
             .health_configs
@@ -168,6 +191,7 @@ impl StructureBundle {
         let structure = Structure {
             health_configs,
             health: Health::same_health(structure_config.starting_health),
+            key: structure_config.key.clone(),
             structure_type: structure_config.structure_type.clone(),
             ..Default::default()
         };
@@ -176,9 +200,7 @@ impl StructureBundle {
         Self {
             structure,
             body: Body {
-                tile_size,
                 underground: false,
-                cell_center: position,
                 visibility_before_inventory: false,
             },
             sprite: Self::sprite(
@@ -191,6 +213,29 @@ impl StructureBundle {
         }
     }
 
+    pub fn with_health_scale(mut self, scale: f32) -> Self {
+        let scale_health = |health: i32| (health as f32 * scale).ceil() as i32;
+
+        // Ranges are scaled from their lower bound's predecessor so they stay contiguous
+        for structure_health in self.structure.health_configs.iter_mut() {
+            let texture = &mut structure_health.health_texture;
+            texture.min_health = scale_health(texture.min_health - 1) + 1;
+            texture.max_health = scale_health(texture.max_health);
+        }
+        self.structure.health = Health::same_health(scale_health(self.structure.health.max_health));
+
+        self
+    }
+
+    pub fn with_current_health(mut self, current_health: i32) -> Self {
+        self.structure.health.current_health = current_health;
+        if let Some(sprite_index) = self.structure.current_texture_index() {
+            self.sprite.sprite.index = sprite_index;
+        }
+
+        self
+    }
+
     fn sprite(
         atlas: &Handle<TextureAtlas>,
         position: Vec3,
@@ -10,6 +10,7 @@ pub enum ToolType {
     Hammer,
     Hoe,
     Shovel,
+    WateringCan,
 }
 
 #[derive(Debug)]
@@ -24,7 +25,8 @@ impl FromStr for ToolType {
             "hammer" => Ok(ToolType::Hammer),
             "hoe" => Ok(ToolType::Hoe),
             "shovel" => Ok(ToolType::Shovel),
-            _ => Ok(ToolType::PickAxe),
+            "watering_can" => Ok(ToolType::WateringCan),
+            _ => Err(ParseToolTypeError),
         }
     }
 }
@@ -35,6 +37,7 @@ pub struct Tool {
     pub key: String,
     pub tool_type: ToolType,
     pub damage: Option<Damage>,
+    pub water: u32,
 }
 
 impl Tool {
@@ -45,4 +48,8 @@ impl Tool {
     pub fn can_clear(&self) -> bool {
         self.tool_type == ToolType::Hoe
     }
+
+    pub fn can_water(&self) -> bool {
+        self.tool_type == ToolType::WateringCan
+    }
 }
@@ -7,4 +7,5 @@ pub use timer::WorldTickTimer;
 #[derive(Component, Default)]
 pub struct World {
     pub tick_just_finished: bool,
+    pub ticks: u64,
 }
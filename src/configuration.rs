@@ -1,11 +1,17 @@
+pub mod controls;
 pub mod crops;
+pub mod errors;
 pub mod floors;
 pub mod game;
+pub mod items;
 pub mod kdl_utils;
 pub mod key_selector;
+pub mod layers;
 pub mod map;
+pub mod notes;
 pub mod player;
 pub mod structures;
 pub mod timers;
 pub mod tools;
+pub mod validation;
 pub mod world;
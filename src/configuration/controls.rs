@@ -0,0 +1,218 @@
+use std::{fmt, str::FromStr};
+
+use bevy::prelude::{GamepadButtonType, KeyCode};
+use kdl::KdlNode;
+
+use crate::states::AppState;
+
+use super::{
+    errors::{in_file, ConfigError, ConfigErrorKind},
+    kdl_utils::{
+        button_selector_field, find_node, float_field, key_selector_field, parse, parsed_name,
+        try_nodes,
+    },
+    key_selector::{ButtonSelector, KeySelector},
+    timers::CONSTANT_MOVE_WAIT_TIME,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveNorth,
+    MoveSouth,
+    MoveEast,
+    MoveWest,
+    Interact,
+    Zoom,
+    ToggleCoordinates,
+    ToggleMap,
+    OpenInventory,
+    SelectPrevious,
+    SelectNext,
+    ReadNotes,
+    PreviousPage,
+    NextPage,
+    Save,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 15] = [
+        Self::MoveNorth,
+        Self::MoveSouth,
+        Self::MoveEast,
+        Self::MoveWest,
+        Self::Interact,
+        Self::Zoom,
+        Self::ToggleCoordinates,
+        Self::ToggleMap,
+        Self::OpenInventory,
+        Self::SelectPrevious,
+        Self::SelectNext,
+        Self::ReadNotes,
+        Self::PreviousPage,
+        Self::NextPage,
+        Self::Save,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MoveNorth => "move_north",
+            Self::MoveSouth => "move_south",
+            Self::MoveEast => "move_east",
+            Self::MoveWest => "move_west",
+            Self::Interact => "interact",
+            Self::Zoom => "zoom",
+            Self::ToggleCoordinates => "toggle_coordinates",
+            Self::ToggleMap => "toggle_map",
+            Self::OpenInventory => "open_inventory",
+            Self::SelectPrevious => "select_previous",
+            Self::SelectNext => "select_next",
+            Self::ReadNotes => "read_notes",
+            Self::PreviousPage => "previous_page",
+            Self::NextPage => "next_page",
+            Self::Save => "save",
+        }
+    }
+
+    // Two actions can share a key as long as they are never listened for in the same state
+    pub fn states(&self) -> &'static [AppState] {
+        match self {
+            Self::MoveNorth
+            | Self::MoveSouth
+            | Self::MoveEast
+            | Self::MoveWest
+            | Self::Interact
+            | Self::Zoom
+            | Self::ToggleCoordinates
+            | Self::ToggleMap => &[AppState::InGame],
+            Self::OpenInventory => &[AppState::InGame, AppState::InventoryScreen],
+            Self::SelectPrevious | Self::SelectNext => &[AppState::InventoryScreen],
+            Self::ReadNotes => &[AppState::InGame, AppState::ReadingNote],
+            Self::PreviousPage | Self::NextPage => &[AppState::ReadingNote],
+            Self::Save => &[
+                AppState::InGame,
+                AppState::InventoryScreen,
+                AppState::ReadingNote,
+            ],
+        }
+    }
+}
+
+impl fmt::Display for InputAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseInputActionError;
+
+impl FromStr for InputAction {
+    type Err = ParseInputActionError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name() == value)
+            .ok_or(ParseInputActionError)
+    }
+}
+
+// A binding needs a key, a gamepad button or both
+pub struct ActionBinding {
+    pub action: InputAction,
+    pub key: Option<KeySelector>,
+    pub button: Option<ButtonSelector>,
+}
+
+impl TryFrom<&KdlNode> for ActionBinding {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let action = parsed_name(node, &mut errors, ConfigErrorKind::UnknownInputAction);
+        let button = button_selector_field(node, "button", &mut errors);
+        let key = match (node.get("key"), node.get("button")) {
+            (None, Some(_)) => None,
+            _ => key_selector_field(node, "key", &mut errors),
+        };
+
+        match action {
+            Some(action) if errors.is_empty() => Ok(Self {
+                action,
+                key,
+                button,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+const DEFAULT_STICK_THRESHOLD: f32 = 0.5;
+
+pub struct ControlsConfig {
+    pub bindings: Vec<ActionBinding>,
+    pub repeat_delay: f32,
+    pub stick_threshold: f32,
+}
+
+impl ControlsConfig {
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let bindings = try_nodes(
+            nodes.iter().filter(|node| node.name().value() == "action"),
+            &mut errors,
+        );
+        let repeat_delay = find_node(&nodes, "movement")
+            .and_then(|node| float_field(node, "repeat_delay", &mut errors))
+            .map_or(CONSTANT_MOVE_WAIT_TIME, |it| it as f32);
+        let stick_threshold = find_node(&nodes, "gamepad")
+            .and_then(|node| float_field(node, "stick_threshold", &mut errors))
+            .map_or(DEFAULT_STICK_THRESHOLD, |it| it as f32);
+
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
+
+        Ok(Self {
+            bindings,
+            repeat_delay,
+            stick_threshold,
+        })
+    }
+
+    pub fn key_codes(&self, action: InputAction) -> impl Iterator<Item = KeyCode> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.action == action)
+            .filter_map(|binding| binding.key.as_ref().map(|key| key.key_code))
+    }
+
+    pub fn buttons(&self, action: InputAction) -> impl Iterator<Item = GamepadButtonType> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |binding| binding.action == action)
+            .filter_map(|binding| binding.button.as_ref().map(|button| button.button))
+    }
+
+    // Every key and button bound to an action, as they are written in controls.kdl
+    pub fn display(&self, action: InputAction) -> String {
+        self.bindings
+            .iter()
+            .filter(|binding| binding.action == action)
+            .flat_map(|binding| {
+                [
+                    binding.key.as_ref().map(|key| key.display_code.clone()),
+                    binding
+                        .button
+                        .as_ref()
+                        .map(|button| button.display_name.clone()),
+                ]
+            })
+            .flatten()
+            .collect::<Vec<String>>()
+            .join(" or ")
+    }
+}
@@ -1,9 +1,14 @@
 use super::{
-    kdl_utils::{parse, parse_key_code},
+    errors::{in_file, ConfigError, ConfigErrorKind},
+    floors::SoilConfig,
+    kdl_utils::{
+        bool_field, child_nodes, integer_field, key_selector_field, name_entry, parse,
+        string_field, try_nodes,
+    },
     key_selector::KeySelector,
 };
 
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 use rand::Rng;
 
 #[derive(Clone)]
@@ -12,54 +17,54 @@ struct CropStageFileConfig {
     min_ticks_in_stage: u32,
     max_ticks_in_stage: u32,
     chance_to_advance: u32,
+    moisture: u32,
+    neglect_ticks: u32,
 }
 
 const DEFAULT_MIN_TICK: u32 = 10;
+const DEFAULT_STARTER_QUANTITY: u32 = 5;
+const DEFAULT_PRODUCE_YIELD: u32 = 1;
+const DEFAULT_SEED_YIELD: u32 = 0;
 const DEFAULT_MAX_TICK: u32 = 15;
 const DEFAULT_CHANCE_TO_ADVANCE: u32 = 10;
+const DEFAULT_MOISTURE: u32 = 0;
+const DEFAULT_NEGLECT_TICKS: u32 = 60;
+const DEFAULT_DEAD_TICKS: u32 = 60;
+const DEFAULT_PEST_CHANCE: u32 = 0;
+const DEFAULT_MAX_DENSITY: u32 = 20;
+const DEFAULT_HYBRID_TIMING: u32 = 100;
+const MIN_SOIL_AFFINITY: u32 = 25;
 
-impl From<&KdlNode> for CropStageFileConfig {
-    fn from(node: &KdlNode) -> Self {
-        let sprite = match node.get("sprite") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
+impl TryFrom<&KdlNode> for CropStageFileConfig {
+    type Error = Vec<ConfigError>;
 
-        let min_ticks = match node.get("min_ticks") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as u32,
-                _ => DEFAULT_MIN_TICK,
-            },
-            _ => DEFAULT_MIN_TICK,
-        };
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
 
-        let max_ticks = match node.get("max_ticks") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as u32,
-                _ => DEFAULT_MAX_TICK,
-            },
-            _ => DEFAULT_MAX_TICK,
-        };
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+        let min_ticks =
+            integer_field(node, "min_ticks", &mut errors).map_or(DEFAULT_MIN_TICK, |it| it as u32);
+        let max_ticks =
+            integer_field(node, "max_ticks", &mut errors).map_or(DEFAULT_MAX_TICK, |it| it as u32);
+        let advance_chance = integer_field(node, "advance_chance", &mut errors)
+            .map_or(DEFAULT_CHANCE_TO_ADVANCE, |it| it as u32);
+        let moisture =
+            integer_field(node, "moisture", &mut errors).map_or(DEFAULT_MOISTURE, |it| it as u32);
+        let neglect_ticks = integer_field(node, "neglect_ticks", &mut errors)
+            .map_or(DEFAULT_NEGLECT_TICKS, |it| it as u32);
 
-        let advance_chance = match node.get("advance_chance") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as u32,
-                _ => DEFAULT_CHANCE_TO_ADVANCE,
-            },
-            _ => DEFAULT_CHANCE_TO_ADVANCE,
-        };
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
-        Self {
+        Ok(Self {
             sprite_location: sprite,
             min_ticks_in_stage: min_ticks,
             max_ticks_in_stage: max_ticks,
             chance_to_advance: advance_chance,
-        }
+            moisture,
+            neglect_ticks,
+        })
     }
 }
 
@@ -70,6 +75,133 @@ pub struct CropConfiguration {
     pub stages: Vec<CropStage>,
     pub inventory_selector: KeySelector,
     pub starter: bool,
+    pub starter_quantity: u32,
+    pub harvest: HarvestYield,
+    pub soil: SoilPreference,
+    pub withered: WitheredStage,
+    pub pest_chance: u32,
+    pub spread: Option<SpreadConfig>,
+    pub breeding: Option<BreedingRecipe>,
+}
+
+impl CropConfiguration {
+    // Percent of the normal growth speed on the given soil, fertile soil speeds the crop up and
+    // every point the soil is off from what it prefers slows it down
+    pub fn growth_rate(&self, soil: &SoilConfig) -> u32 {
+        let affinity = |preferred: Option<u32>, actual: u32| match preferred {
+            Some(preferred) => 100u32
+                .saturating_sub(preferred.abs_diff(actual))
+                .max(MIN_SOIL_AFFINITY),
+            _ => 100,
+        };
+
+        soil.fertility * affinity(self.soil.light, soil.light) / 100
+            * affinity(self.soil.drainage, soil.drainage)
+            / 100
+    }
+}
+
+// Mature crops that spread have `chance` in 10000 each world tick to seed a neighbouring tile,
+// until the floor holds `max_density` of them
+#[derive(Clone, Copy)]
+pub struct SpreadConfig {
+    pub chance: u32,
+    pub max_density: u32,
+}
+
+impl TryFrom<&KdlNode> for SpreadConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let chance = integer_field(node, "chance", &mut errors).map_or(0, |it| it as u32);
+        let max_density = integer_field(node, "max_density", &mut errors)
+            .map_or(DEFAULT_MAX_DENSITY, |it| it as u32);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self {
+            chance,
+            max_density,
+        })
+    }
+}
+
+// The two neighbouring mature crops a hybrid comes from, harvesting either of them has `chance`
+// in 100 to also yield a hybrid seed
+#[derive(Clone)]
+pub struct BreedingRecipe {
+    pub base: String,
+    pub partner: String,
+    pub chance: u32,
+}
+
+impl BreedingRecipe {
+    pub fn crosses(&self, first: &str, second: &str) -> bool {
+        (self.base == first && self.partner == second)
+            || (self.base == second && self.partner == first)
+    }
+}
+
+// The soil a crop grows best in, anything it leaves out suits it everywhere
+#[derive(Clone, Copy, Default)]
+pub struct SoilPreference {
+    pub light: Option<u32>,
+    pub drainage: Option<u32>,
+}
+
+impl TryFrom<&KdlNode> for SoilPreference {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let light = integer_field(node, "light", &mut errors).map(|it| it as u32);
+        let drainage = integer_field(node, "drainage", &mut errors).map(|it| it as u32);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self { light, drainage })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct HarvestYield {
+    pub produce: u32,
+    pub seeds: u32,
+}
+
+impl Default for HarvestYield {
+    fn default() -> Self {
+        Self {
+            produce: DEFAULT_PRODUCE_YIELD,
+            seeds: DEFAULT_SEED_YIELD,
+        }
+    }
+}
+
+impl TryFrom<&KdlNode> for HarvestYield {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let produce = integer_field(node, "produce", &mut errors)
+            .map_or(DEFAULT_PRODUCE_YIELD, |it| it as u32);
+        let seeds =
+            integer_field(node, "seeds", &mut errors).map_or(DEFAULT_SEED_YIELD, |it| it as u32);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self { produce, seeds })
+    }
 }
 
 #[derive(Clone)]
@@ -80,11 +212,10 @@ pub struct CropStage {
 }
 
 impl CropStage {
-    pub fn configured_ticks_in_stage(&self) -> u32 {
+    pub fn configured_ticks_in_stage(&self, rng: &mut impl Rng) -> u32 {
         let mut ticks = self.file_config.min_ticks_in_stage;
 
         if self.file_config.min_ticks_in_stage != self.file_config.max_ticks_in_stage {
-            let mut rng = rand::thread_rng();
             ticks = rng.gen_range(
                 self.file_config.min_ticks_in_stage..self.file_config.max_ticks_in_stage,
             );
@@ -97,9 +228,144 @@ impl CropStage {
         &self.file_config.sprite_location[..]
     }
 
+    // The same stage taking `percent` of the time, which is all that sets a hybrid's stages apart
+    fn with_timing(&self, percent: u32) -> Self {
+        let mut stage = self.clone();
+        stage.file_config.min_ticks_in_stage = self.file_config.min_ticks_in_stage * percent / 100;
+        stage.file_config.max_ticks_in_stage = self.file_config.max_ticks_in_stage * percent / 100;
+        stage
+    }
+
     pub fn chance_to_advance(&self) -> u32 {
         self.file_config.chance_to_advance
     }
+
+    // Percent of soil moisture the stage needs to advance at its full chance
+    pub fn moisture_needed(&self) -> u32 {
+        self.file_config.moisture
+    }
+
+    // How long the stage survives dry or poor soil before it withers
+    pub fn ticks_until_withered(&self) -> u32 {
+        self.ticks_per_second * self.file_config.neglect_ticks
+    }
+}
+
+#[derive(Clone)]
+struct WitheredFileConfig {
+    sprite_location: String,
+    ticks_until_dead: u32,
+}
+
+impl TryFrom<&KdlNode> for WitheredFileConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+        let ticks =
+            integer_field(node, "ticks", &mut errors).map_or(DEFAULT_DEAD_TICKS, |it| it as u32);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self {
+            sprite_location: sprite,
+            ticks_until_dead: ticks,
+        })
+    }
+}
+
+// Where neglected crops end up, they hang on for a while and then die off
+#[derive(Clone)]
+pub struct WitheredStage {
+    file_config: WitheredFileConfig,
+    pub sprite_index: Option<usize>,
+    pub ticks_per_second: u32,
+}
+
+impl WitheredStage {
+    pub fn sprite_location(&self) -> &str {
+        &self.file_config.sprite_location[..]
+    }
+
+    pub fn ticks_until_dead(&self) -> u32 {
+        self.ticks_per_second * self.file_config.ticks_until_dead
+    }
+}
+
+fn crop_field<'a>(
+    node: &KdlNode,
+    field: &str,
+    crops: &'a [CropConfiguration],
+    errors: &mut Vec<ConfigError>,
+) -> Option<&'a CropConfiguration> {
+    let entry = match node.get(field) {
+        Some(it) => it,
+        _ => {
+            errors.push(ConfigError::missing_field(node, field));
+            return None;
+        }
+    };
+    let key = string_field(node, field, errors)?;
+
+    match crops.iter().find(|crop| crop.key == key) {
+        Some(it) => Some(it),
+        _ => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::UnknownCrop(key),
+            ));
+            None
+        }
+    }
+}
+
+// Hybrids start out as a copy of their base crop, so anything they don't set is inherited from it
+fn hybrid_config(
+    node: &KdlNode,
+    crops: &[CropConfiguration],
+    errors: &mut Vec<ConfigError>,
+) -> Option<CropConfiguration> {
+    let name = name_entry(node, errors).unwrap_or_default();
+    let key = string_field(node, "key", errors).unwrap_or_default();
+    let inventory_selector = key_selector_field(node, "key_code", errors);
+    let chance = integer_field(node, "chance", errors).map_or(0, |it| it as u32);
+    let timing =
+        integer_field(node, "timing", errors).map_or(DEFAULT_HYBRID_TIMING, |it| it as u32);
+    let base = crop_field(node, "base", crops, errors);
+    let partner = crop_field(node, "partner", crops, errors);
+    let harvest = try_nodes::<HarvestYield>(
+        child_nodes(node).filter(|node| node.name().value() == "harvest"),
+        errors,
+    )
+    .into_iter()
+    .next();
+
+    let (base, partner) = (base?, partner?);
+    Some(CropConfiguration {
+        key,
+        name,
+        stages: base
+            .stages
+            .iter()
+            .map(|stage| stage.with_timing(timing))
+            .collect(),
+        inventory_selector: inventory_selector?,
+        starter: false,
+        starter_quantity: 0,
+        harvest: harvest.unwrap_or(base.harvest),
+        breeding: Some(BreedingRecipe {
+            base: base.key.clone(),
+            partner: partner.key.clone(),
+            chance,
+        }),
+        ..base.clone()
+    })
 }
 
 pub struct CropsConfig {
@@ -107,69 +373,206 @@ pub struct CropsConfig {
 }
 
 impl CropsConfig {
-    pub fn load(path: &str, world_tick_time: f32) -> Self {
-        let crop_nodes = parse(path).unwrap();
-        let configurations: Vec<CropConfiguration> = crop_nodes
+    pub fn config_by_key(&self, key: &str) -> Option<&CropConfiguration> {
+        self.configurations.iter().find(|c| c.key == key)
+    }
+
+    pub fn load(path: &str, world_tick_time: f32) -> Result<Self, Vec<ConfigError>> {
+        let crop_nodes = parse(path).map_err(|e| vec![e])?;
+        let mut errors = Vec::new();
+        let mut configurations: Vec<CropConfiguration> = crop_nodes
             .iter()
-            .map(|crop_node| {
-                let name = match crop_node.entries().get(0) {
-                    Some(entry) => match entry.value() {
-                        KdlValue::RawString(it) | KdlValue::String(it) => {
-                            super::kdl_utils::trim(it.clone())
-                        }
-                        _ => "".to_string(),
-                    },
-                    _ => "".to_string(),
-                };
-                let key = match crop_node.get("key") {
-                    Some(entry) => match entry.value() {
-                        KdlValue::RawString(it) | KdlValue::String(it) => {
-                            super::kdl_utils::trim(it.clone())
-                        }
-                        _ => "".to_string(),
-                    },
-                    _ => "".to_string(),
-                };
-                let key_code = match crop_node.get("key_code") {
-                    Some(entry) => match entry.value() {
-                        KdlValue::RawString(it) | KdlValue::String(it) => {
-                            super::kdl_utils::trim(it.clone())
-                        }
-                        _ => "".to_string(),
-                    },
-                    _ => "".to_string(),
-                };
-                let starter = match crop_node.get("starter") {
-                    Some(entry) => match entry.value() {
-                        KdlValue::Bool(it) => *it,
-                        _ => false,
-                    },
-                    _ => false,
-                };
-                let stages: Vec<CropStage> = crop_node
-                    .children()
-                    .iter()
-                    .flat_map(|doc| doc.nodes())
-                    .map(|stage_node| CropStage {
-                        ticks_per_second: (1.0 / world_tick_time) as u32,
-                        sprite_index: None,
-                        file_config: CropStageFileConfig::from(stage_node),
-                    })
-                    .collect();
-
-                CropConfiguration {
+            .filter(|node| node.name().value() == "crop")
+            .filter_map(|crop_node| {
+                let name = name_entry(crop_node, &mut errors).unwrap_or_default();
+                let key = string_field(crop_node, "key", &mut errors).unwrap_or_default();
+                let inventory_selector = key_selector_field(crop_node, "key_code", &mut errors);
+                let starter = bool_field(crop_node, "starter", &mut errors).unwrap_or(false);
+                let starter_quantity = integer_field(crop_node, "starter_quantity", &mut errors)
+                    .map_or(DEFAULT_STARTER_QUANTITY, |it| it as u32);
+                let pest_chance = integer_field(crop_node, "pest_chance", &mut errors)
+                    .map_or(DEFAULT_PEST_CHANCE, |it| it as u32);
+                let stage_nodes =
+                    child_nodes(crop_node).filter(|node| node.name().value() == "stage");
+                let stages: Vec<CropStage> =
+                    try_nodes::<CropStageFileConfig>(stage_nodes, &mut errors)
+                        .into_iter()
+                        .map(|file_config| CropStage {
+                            ticks_per_second: (1.0 / world_tick_time) as u32,
+                            sprite_index: None,
+                            file_config,
+                        })
+                        .collect();
+                let harvest = try_nodes::<HarvestYield>(
+                    child_nodes(crop_node).filter(|node| node.name().value() == "harvest"),
+                    &mut errors,
+                )
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+                let soil = try_nodes::<SoilPreference>(
+                    child_nodes(crop_node).filter(|node| node.name().value() == "soil"),
+                    &mut errors,
+                )
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+                let spread = try_nodes::<SpreadConfig>(
+                    child_nodes(crop_node).filter(|node| node.name().value() == "spread"),
+                    &mut errors,
+                )
+                .into_iter()
+                .next();
+                let withered = try_nodes::<WitheredFileConfig>(
+                    child_nodes(crop_node).filter(|node| node.name().value() == "withered"),
+                    &mut errors,
+                )
+                .into_iter()
+                .next()
+                .map(|file_config| WitheredStage {
+                    ticks_per_second: (1.0 / world_tick_time) as u32,
+                    sprite_index: None,
+                    file_config,
+                });
+                if withered.is_none()
+                    && !child_nodes(crop_node).any(|node| node.name().value() == "withered")
+                {
+                    errors.push(ConfigError::missing_node("withered"));
+                }
+
+                Some(CropConfiguration {
                     name,
                     stages,
                     key,
                     starter,
-                    inventory_selector: KeySelector {
-                        key_code: parse_key_code(&key_code).unwrap(),
-                        display_code: key_code.clone(),
-                    },
-                }
+                    starter_quantity,
+                    harvest,
+                    soil,
+                    pest_chance,
+                    spread,
+                    breeding: None,
+                    withered: withered?,
+                    inventory_selector: inventory_selector?,
+                })
             })
             .collect();
 
-        Self { configurations }
+        // Hybrids can be bred from crops or from hybrids listed before them
+        for hybrid_node in crop_nodes
+            .iter()
+            .filter(|node| node.name().value() == "hybrid")
+        {
+            if let Some(hybrid) = hybrid_config(hybrid_node, &configurations, &mut errors) {
+                configurations.push(hybrid);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
+
+        Ok(Self { configurations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        configuration::{errors::ConfigErrorKind, floors::SoilConfig},
+        headless::FIXTURE_CONFIG_PATH,
+    };
+
+    use super::{CropConfiguration, CropsConfig};
+
+    const INVALID_CROPS_PATH: &str = "tests/fixtures/invalid/crops.kdl";
+
+    #[test]
+    fn every_bad_field_is_reported_in_one_load() {
+        let errors = match CropsConfig::load(INVALID_CROPS_PATH, 0.2) {
+            Err(it) => it,
+            _ => panic!("{INVALID_CROPS_PATH} should not load"),
+        };
+
+        let reported: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|error| {
+                (
+                    error.node_name.as_deref().unwrap_or_default(),
+                    error.field.as_deref().unwrap_or_default(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                ("crop", "key_code"),
+                ("stage", "min_ticks"),
+                ("harvest", "produce"),
+                ("crop", "starter"),
+                ("stage", "advance_chance"),
+                ("hybrid", "partner"),
+            ]
+        );
+
+        assert!(errors.iter().all(|error| error.path == INVALID_CROPS_PATH));
+        assert!(errors.iter().all(|error| error.span.is_some()));
+        assert!(matches!(
+            &*errors[0].kind,
+            ConfigErrorKind::UnknownKeyCode(code) if code == "not_a_key"
+        ));
+        assert!(matches!(
+            &*errors[3].kind,
+            ConfigErrorKind::InvalidType { expected: "bool" }
+        ));
+        assert!(matches!(
+            &*errors[5].kind,
+            ConfigErrorKind::UnknownCrop(key) if key == "turnip"
+        ));
+    }
+
+    // Kane prefers light=60 and drainage=100
+    fn kane() -> CropConfiguration {
+        let crops_config =
+            CropsConfig::load(&format!("{FIXTURE_CONFIG_PATH}/crops.kdl"), 0.2).unwrap();
+        crops_config.config_by_key("kane").unwrap().clone()
+    }
+
+    #[test]
+    fn matching_soil_grows_at_the_normal_rate() {
+        let soil = SoilConfig {
+            fertility: 100,
+            drainage: 100,
+            light: 60,
+        };
+
+        assert_eq!(kane().growth_rate(&soil), 100);
+    }
+
+    #[test]
+    fn soil_far_off_the_preference_still_grows_at_the_minimum_affinity() {
+        let soil = SoilConfig {
+            fertility: 100,
+            drainage: 200,
+            light: 60,
+        };
+
+        assert_eq!(kane().growth_rate(&soil), 25);
+    }
+
+    #[test]
+    fn fertility_scales_the_growth_rate() {
+        let rich = SoilConfig {
+            fertility: 150,
+            drainage: 100,
+            light: 60,
+        };
+        let poor = SoilConfig {
+            fertility: 50,
+            drainage: 90,
+            light: 60,
+        };
+
+        assert_eq!(kane().growth_rate(&rich), 150);
+        assert_eq!(kane().growth_rate(&poor), 45);
     }
 }
@@ -0,0 +1,168 @@
+use std::{fmt, io};
+
+use kdl::{KdlEntry, KdlError, KdlNode};
+
+#[derive(Debug)]
+pub enum ConfigErrorKind {
+    InvalidPath(io::Error),
+    InvalidKdl(KdlError),
+    MissingNode,
+    MissingField,
+    InvalidType { expected: &'static str },
+    UnknownKeyCode(String),
+    UnknownStructureType(String),
+    UnknownToolType(String),
+    UnknownItemKind(String),
+    UnknownRarity(String),
+    UnknownLayerType(String),
+    UnknownLayerContent(String),
+    UnknownInputAction(String),
+    UnknownGamepadButton(String),
+    UnknownCrop(String),
+}
+
+impl fmt::Display for ConfigErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPath(e) => write!(f, "could not read file ({e})"),
+            Self::InvalidKdl(e) => write!(f, "invalid kdl ({e})"),
+            Self::MissingNode => write!(f, "node is missing"),
+            Self::MissingField => write!(f, "field is missing"),
+            Self::InvalidType { expected } => write!(f, "expected a {expected}"),
+            Self::UnknownKeyCode(code) => write!(f, "unknown key code \"{code}\""),
+            Self::UnknownStructureType(value) => write!(f, "unknown structure type \"{value}\""),
+            Self::UnknownToolType(value) => write!(f, "unknown tool type \"{value}\""),
+            Self::UnknownItemKind(value) => write!(f, "unknown item kind \"{value}\""),
+            Self::UnknownRarity(value) => write!(f, "unknown rarity \"{value}\""),
+            Self::UnknownLayerType(value) => write!(f, "unknown layer type \"{value}\""),
+            Self::UnknownLayerContent(value) => write!(f, "unknown layer content `{value}`"),
+            Self::UnknownInputAction(value) => write!(f, "unknown input action \"{value}\""),
+            Self::UnknownGamepadButton(value) => write!(f, "unknown gamepad button \"{value}\""),
+            Self::UnknownCrop(value) => write!(f, "unknown crop \"{value}\""),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ConfigSpan {
+    pub offset: usize,
+    pub len: usize,
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: String,
+    pub span: Option<ConfigSpan>,
+    pub node_name: Option<String>,
+    pub field: Option<String>,
+    // Boxed, a KdlError inside the kind would otherwise make every Result carrying this large
+    pub kind: Box<ConfigErrorKind>,
+}
+
+impl ConfigError {
+    pub fn file(path: &str, kind: ConfigErrorKind) -> Self {
+        let span = match &kind {
+            ConfigErrorKind::InvalidKdl(e) => Some(ConfigSpan {
+                offset: e.span.offset(),
+                len: e.span.len(),
+            }),
+            _ => None,
+        };
+
+        Self {
+            path: path.to_string(),
+            span,
+            node_name: None,
+            field: None,
+            kind: Box::new(kind),
+        }
+    }
+
+    pub fn node(node: &KdlNode, kind: ConfigErrorKind) -> Self {
+        Self {
+            path: String::new(),
+            span: Some(ConfigSpan {
+                offset: node.span().offset(),
+                len: node.span().len(),
+            }),
+            node_name: Some(node.name().value().to_string()),
+            field: None,
+            kind: Box::new(kind),
+        }
+    }
+
+    pub fn missing_node(name: &str) -> Self {
+        Self {
+            path: String::new(),
+            span: None,
+            node_name: Some(name.to_string()),
+            field: None,
+            kind: Box::new(ConfigErrorKind::MissingNode),
+        }
+    }
+
+    pub fn missing_field(node: &KdlNode, field: &str) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            ..Self::node(node, ConfigErrorKind::MissingField)
+        }
+    }
+
+    pub fn field(node: &KdlNode, field: &str, entry: &KdlEntry, kind: ConfigErrorKind) -> Self {
+        Self {
+            span: Some(ConfigSpan {
+                offset: entry.span().offset(),
+                len: entry.span().len(),
+            }),
+            field: Some(field.to_string()),
+            ..Self::node(node, kind)
+        }
+    }
+
+    pub fn in_file(self, path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+
+        if let Some(span) = self.span {
+            write!(f, " [{}..{}]", span.offset, span.offset + span.len)?;
+        }
+
+        if let Some(node_name) = &self.node_name {
+            write!(f, " node `{node_name}`")?;
+        }
+
+        if let Some(field) = &self.field {
+            write!(f, " field `{field}`")?;
+        }
+
+        write!(f, ": {}", self.kind)
+    }
+}
+
+pub fn in_file(errors: Vec<ConfigError>, path: &str) -> Vec<ConfigError> {
+    errors
+        .into_iter()
+        .map(|error| error.in_file(path))
+        .collect()
+}
+
+pub fn keep_errors<T>(
+    result: Result<T, Vec<ConfigError>>,
+    errors: &mut Vec<ConfigError>,
+) -> Option<T> {
+    match result {
+        Ok(it) => Some(it),
+        Err(mut e) => {
+            errors.append(&mut e);
+            None
+        }
+    }
+}
@@ -1,6 +1,13 @@
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 
-use super::kdl_utils::parse;
+use super::{
+    errors::{in_file, ConfigError},
+    kdl_utils::{bool_field, child_nodes, integer_field, parse, string_field, try_nodes},
+};
+
+const DEFAULT_FERTILITY: u32 = 100;
+const DEFAULT_DRAINAGE: u32 = 100;
+const DEFAULT_LIGHT: u32 = 50;
 
 pub struct FloorsConfig {
     pub configurations: Vec<FloorConfig>,
@@ -13,45 +20,79 @@ impl FloorsConfig {
 }
 
 impl FloorsConfig {
-    pub fn load(path: &str) -> Self {
-        let floor_nodes = parse(path).unwrap();
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let floor_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let configurations = try_nodes(floor_nodes.iter(), &mut errors);
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
 
-        let configurations = floor_nodes.iter().map(FloorConfig::from).collect();
-        Self { configurations }
+        Ok(Self { configurations })
     }
 }
 
 pub struct FloorConfig {
     pub key: String,
+    pub tillable: bool,
+    pub soil: SoilConfig,
     pub sprite_options: Vec<FloorSpriteConfig>,
 }
 
-impl From<&KdlNode> for FloorConfig {
-    fn from(node: &KdlNode) -> Self {
-        let key = match node.get("key") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
+// Fertility and drainage are percents of the normal growth speed and moisture loss, light runs
+// from 0 for pitch black to 100
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoilConfig {
+    pub fertility: u32,
+    pub drainage: u32,
+    pub light: u32,
+}
+
+impl Default for SoilConfig {
+    fn default() -> Self {
+        Self {
+            fertility: DEFAULT_FERTILITY,
+            drainage: DEFAULT_DRAINAGE,
+            light: DEFAULT_LIGHT,
+        }
+    }
+}
+
+impl TryFrom<&KdlNode> for FloorConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let key = string_field(node, "key", &mut errors).unwrap_or_default();
+        let tillable = bool_field(node, "tillable", &mut errors).unwrap_or(false);
+        let soil = SoilConfig {
+            fertility: integer_field(node, "fertility", &mut errors)
+                .map_or(DEFAULT_FERTILITY, |it| it as u32),
+            drainage: integer_field(node, "drainage", &mut errors)
+                .map_or(DEFAULT_DRAINAGE, |it| it as u32),
+            light: integer_field(node, "light", &mut errors).map_or(DEFAULT_LIGHT, |it| it as u32),
         };
 
-        let sprite_options = node
-            .children()
-            .iter()
-            .flat_map(|doc| doc.nodes())
-            .map(|it| FloorSpriteConfig {
-                file_option: FloorFileOption::from(it),
+        let sprite_options = try_nodes::<FloorFileOption>(child_nodes(node), &mut errors)
+            .into_iter()
+            .map(|file_option| FloorSpriteConfig {
+                file_option,
                 sprite_index: None,
             })
             .collect();
 
-        Self {
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self {
             sprite_options,
             key,
-        }
+            tillable,
+            soil,
+        })
     }
 }
 
@@ -70,18 +111,18 @@ pub struct FloorFileOption {
     pub sprite: String,
 }
 
-impl From<&KdlNode> for FloorFileOption {
-    fn from(node: &KdlNode) -> Self {
-        let sprite = match node.get("sprite") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
+impl TryFrom<&KdlNode> for FloorFileOption {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
-        Self { sprite }
+        Ok(Self { sprite })
     }
 }
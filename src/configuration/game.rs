@@ -1,11 +1,21 @@
 use bevy::{prelude::Resource, time::Timer};
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 use tdlg::generation::Generator;
 
-use crate::configuration::{kdl_utils::parse, world::WorldGenerationConfig};
+use crate::configuration::{
+    kdl_utils::{find_node, float_field, parse, require_node, string_field},
+    world::{WorldGenerationConfig, WorldStatsConfig},
+};
 
 use super::{
-    crops::CropsConfig, floors::FloorsConfig, player::PlayerConfig, structures::StructuresConfig,
+    controls::ControlsConfig,
+    crops::CropsConfig,
+    errors::{in_file, keep_errors, ConfigError},
+    floors::FloorsConfig,
+    items::ItemsConfig,
+    notes::NotesConfig,
+    player::PlayerConfig,
+    structures::StructuresConfig,
     tools::ToolConfigurations,
 };
 
@@ -18,6 +28,9 @@ pub struct GameConfiguration {
     pub world_config: WorldGenerationConfig,
     pub sprite_config: SpriteConfig,
     pub tool_configs: ToolConfigurations,
+    pub items_config: ItemsConfig,
+    pub notes_config: NotesConfig,
+    pub controls_config: ControlsConfig,
     pub seed: String,
     pub world_tick_time: f32,
     level: usize,
@@ -25,7 +38,11 @@ pub struct GameConfiguration {
 
 impl GameConfiguration {
     pub fn map_size(&self) -> u16 {
-        self.world_config.world_stats.map_size
+        self.world_stats().map_size
+    }
+
+    pub fn world_stats(&self) -> WorldStatsConfig {
+        self.world_config.stats_for(self.depth())
     }
 
     pub fn tile_size(&self) -> f32 {
@@ -36,15 +53,33 @@ impl GameConfiguration {
         Timer::from_seconds(self.world_tick_time, bevy::time::TimerMode::Repeating)
     }
 
-    pub fn generator(&mut self, increment: bool) -> Generator {
-        let seed = if increment {
-            self.level += 1;
-            format!("{}|{}", self.seed, self.level)
-        } else {
-            self.seed.clone()
-        };
+    pub fn generator(&self) -> Generator {
+        self.world_config.generator(self.floor_seed(), self.depth())
+    }
 
-        self.world_config.generator(seed)
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn depth(&self) -> usize {
+        self.level + 1
+    }
+
+    pub fn change_level(&mut self, level: usize) {
+        self.level = level;
+    }
+
+    pub fn restore_level(&mut self, seed: String, level: usize) {
+        self.seed = seed;
+        self.level = level;
+    }
+
+    pub fn floor_seed(&self) -> String {
+        if self.level == 0 {
+            self.seed.clone()
+        } else {
+            format!("{}|{}", self.seed, self.level)
+        }
     }
 }
 
@@ -70,69 +105,56 @@ impl Default for SpriteConfig {
     }
 }
 
-impl From<&KdlNode> for SpriteConfig {
-    fn from(node: &KdlNode) -> Self {
-        let size = match node.get("size") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10Float(it) => *it as f32,
-                _ => SpriteConfig::default().size,
-            },
-            _ => SpriteConfig::default().size,
-        };
+impl TryFrom<&KdlNode> for SpriteConfig {
+    type Error = Vec<ConfigError>;
 
-        let crop_scale = match node.get("crop_scale") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10Float(it) => *it as f32,
-                _ => SpriteConfig::default().crop_scale,
-            },
-            _ => SpriteConfig::default().crop_scale,
-        };
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
 
-        let scale = match node.get("scale") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10Float(it) => *it as f32,
-                _ => SpriteConfig::default().scale,
-            },
-            _ => SpriteConfig::default().scale,
-        };
+        let size = float_field(node, "size", &mut errors)
+            .map_or(SpriteConfig::default().size, |it| it as f32);
+        let crop_scale = float_field(node, "crop_scale", &mut errors)
+            .map_or(SpriteConfig::default().crop_scale, |it| it as f32);
+        let scale = float_field(node, "scale", &mut errors)
+            .map_or(SpriteConfig::default().scale, |it| it as f32);
+        let player_scale = float_field(node, "player_scale", &mut errors)
+            .map_or(SpriteConfig::default().player_scale, |it| it as f32);
 
-        let player_scale = match node.get("player_scale") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10Float(it) => *it as f32,
-                _ => SpriteConfig::default().player_scale,
-            },
-            _ => SpriteConfig::default().player_scale,
-        };
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
-        Self {
+        Ok(Self {
             size,
             crop_scale,
             scale,
             player_scale,
-        }
+        })
     }
 }
 
-impl From<&KdlNode> for BasicConfig {
-    fn from(node: &KdlNode) -> Self {
-        let seed = match node.get("seed") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
+impl TryFrom<&KdlNode> for BasicConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let seed = string_field(node, "seed", &mut errors).unwrap_or_default();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
-        Self { seed }
+        Ok(Self { seed })
     }
 }
 
 pub const WORLD_TICK_TIME: f32 = 0.2;
+pub const ASSET_PATH: &str = "./assets";
+pub const CONFIG_PATH: &str = "./assets/config";
 
 impl GameConfiguration {
-    pub fn load(path: &str) -> Self {
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
         let crops_config_path = format!("{path}/crops.kdl");
         let floors_config_path = format!("{path}/floors.kdl");
         let structures_config_path = format!("{path}/structures.kdl");
@@ -140,37 +162,87 @@ impl GameConfiguration {
         let game_config_path = format!("{path}/game.kdl");
         let world_config_path = format!("{path}/world.kdl");
         let tool_config_path = format!("{path}/tools.kdl");
-        let game_config_nodes = parse(&game_config_path).unwrap();
-
-        let basic_node = game_config_nodes
-            .iter()
-            .find(|node| node.name().value().eq_ignore_ascii_case("basic"))
-            .unwrap();
-        let basic_config = BasicConfig::from(basic_node);
-
-        let sprite_config = game_config_nodes
-            .iter()
-            .find(|node| node.name().value().eq_ignore_ascii_case("sprite_stats"))
-            .map_or_else(SpriteConfig::default, SpriteConfig::from);
-
-        let crops_config = CropsConfig::load(&crops_config_path, WORLD_TICK_TIME);
-        let floors_config = FloorsConfig::load(&floors_config_path);
-        let structures_config = StructuresConfig::load(&structures_config_path);
-        let player_config = PlayerConfig::load(&player_config_path);
-        let world_config = WorldGenerationConfig::load(&world_config_path);
-        let tool_configs = ToolConfigurations::load(&tool_config_path);
-
-        Self {
+        let items_config_path = format!("{path}/items.kdl");
+        let notes_config_path = format!("{path}/notes.kdl");
+        let controls_config_path = format!("{path}/controls.kdl");
+
+        let mut errors = Vec::new();
+        let game_file = keep_errors(Self::load_game_file(&game_config_path), &mut errors);
+
+        let crops_config = keep_errors(
+            CropsConfig::load(&crops_config_path, WORLD_TICK_TIME),
+            &mut errors,
+        );
+        let floors_config = keep_errors(FloorsConfig::load(&floors_config_path), &mut errors);
+        let structures_config =
+            keep_errors(StructuresConfig::load(&structures_config_path), &mut errors);
+        let player_config = keep_errors(PlayerConfig::load(&player_config_path), &mut errors);
+        let world_config =
+            keep_errors(WorldGenerationConfig::load(&world_config_path), &mut errors);
+        let tool_configs = keep_errors(ToolConfigurations::load(&tool_config_path), &mut errors);
+        let items_config = keep_errors(ItemsConfig::load(&items_config_path), &mut errors);
+        let notes_config = keep_errors(NotesConfig::load(&notes_config_path), &mut errors);
+        let controls_config = keep_errors(ControlsConfig::load(&controls_config_path), &mut errors);
+
+        match (
+            game_file,
             crops_config,
             floors_config,
             structures_config,
             player_config,
             world_config,
-            sprite_config,
             tool_configs,
-            seed: basic_config.seed,
-            world_tick_time: WORLD_TICK_TIME,
-            level: 0,
+            items_config,
+            notes_config,
+            controls_config,
+        ) {
+            (
+                Some((basic_config, sprite_config)),
+                Some(crops_config),
+                Some(floors_config),
+                Some(structures_config),
+                Some(player_config),
+                Some(world_config),
+                Some(tool_configs),
+                Some(items_config),
+                Some(notes_config),
+                Some(controls_config),
+            ) if errors.is_empty() => Ok(Self {
+                crops_config,
+                floors_config,
+                structures_config,
+                player_config,
+                world_config,
+                sprite_config,
+                tool_configs,
+                items_config,
+                notes_config,
+                controls_config,
+                seed: basic_config.seed,
+                world_tick_time: WORLD_TICK_TIME,
+                level: 0,
+            }),
+            _ => Err(errors),
+        }
+    }
+
+    fn load_game_file(path: &str) -> Result<(BasicConfig, SpriteConfig), Vec<ConfigError>> {
+        let game_config_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let basic_config = require_node(&game_config_nodes, "basic", &mut errors)
+            .and_then(|node| keep_errors(BasicConfig::try_from(node), &mut errors));
+
+        let sprite_config = match find_node(&game_config_nodes, "sprite_stats") {
+            Some(node) => keep_errors(SpriteConfig::try_from(node), &mut errors),
+            _ => Some(SpriteConfig::default()),
+        };
+
+        match (basic_config, sprite_config) {
+            (Some(basic_config), Some(sprite_config)) if errors.is_empty() => {
+                Ok((basic_config, sprite_config))
+            }
+            _ => Err(in_file(errors, path)),
         }
     }
 }
@@ -0,0 +1,158 @@
+use kdl::KdlNode;
+use rand::Rng;
+
+use crate::components::item::{ItemKind, Rarity};
+
+use super::{
+    errors::{in_file, ConfigError, ConfigErrorKind},
+    kdl_utils::{integer_field, name_entry, parse, parsed_field, string_field, try_nodes},
+};
+
+const DEFAULT_STACK_SIZE: u32 = 1;
+
+#[derive(Clone)]
+pub struct ItemFileConfig {
+    name: String,
+    key: String,
+    kind: ItemKind,
+    crop: Option<String>,
+    sprite: String,
+    stack_size: u32,
+    rarity: Rarity,
+}
+
+impl TryFrom<&KdlNode> for ItemFileConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let name = name_entry(node, &mut errors).unwrap_or_default();
+        let key = string_field(node, "key", &mut errors).unwrap_or_default();
+        let kind = parsed_field(node, "kind", &mut errors, ConfigErrorKind::UnknownItemKind);
+        let crop = string_field(node, "crop", &mut errors);
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+        let stack_size = integer_field(node, "stack_size", &mut errors)
+            .map_or(DEFAULT_STACK_SIZE, |it| it as u32);
+        let rarity = match node.get("rarity") {
+            Some(_) => parsed_field(node, "rarity", &mut errors, ConfigErrorKind::UnknownRarity),
+            _ => Some(Rarity::default()),
+        };
+
+        match (kind, rarity) {
+            (Some(kind), Some(rarity)) if errors.is_empty() => Ok(Self {
+                name,
+                key,
+                kind,
+                crop,
+                sprite,
+                stack_size,
+                rarity,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ItemConfiguration {
+    file_config: ItemFileConfig,
+    pub sprite_index: Option<usize>,
+}
+
+impl ItemConfiguration {
+    pub fn key(&self) -> &String {
+        &self.file_config.key
+    }
+
+    pub fn name(&self) -> &String {
+        &self.file_config.name
+    }
+
+    pub fn kind(&self) -> ItemKind {
+        self.file_config.kind
+    }
+
+    pub fn crop(&self) -> Option<&str> {
+        self.file_config.crop.as_deref()
+    }
+
+    pub fn stack_size(&self) -> u32 {
+        self.file_config.stack_size
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        self.file_config.rarity
+    }
+
+    pub fn sprite_location(&self) -> &str {
+        &self.file_config.sprite[..]
+    }
+}
+
+impl TryFrom<&KdlNode> for ItemConfiguration {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let file_config = ItemFileConfig::try_from(node)?;
+
+        Ok(Self {
+            file_config,
+            sprite_index: None,
+        })
+    }
+}
+
+pub struct ItemsConfig {
+    pub configurations: Vec<ItemConfiguration>,
+}
+
+impl ItemsConfig {
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let item_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let configurations = try_nodes(item_nodes.iter(), &mut errors);
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
+
+        Ok(Self { configurations })
+    }
+
+    pub fn config_by_key(&self, key: &str) -> Option<&ItemConfiguration> {
+        self.configurations
+            .iter()
+            .find(|config| config.key() == key)
+    }
+
+    /// Seeds and produce stack as deep as their item, a crop without one isn't capped
+    pub fn crop_stack_size(&self, kind: ItemKind, crop: &str) -> u32 {
+        self.configurations
+            .iter()
+            .find(|config| config.kind() == kind && config.crop() == Some(crop))
+            .map_or(u32::MAX, |config| config.stack_size())
+    }
+
+    pub fn random_by_rarity(&self, rng: &mut impl Rng) -> Option<&ItemConfiguration> {
+        let total: u32 = self
+            .configurations
+            .iter()
+            .map(|config| config.rarity().weight())
+            .sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for config in self.configurations.iter() {
+            let weight = config.rarity().weight();
+            if roll < weight {
+                return Some(config);
+            }
+            roll -= weight;
+        }
+
+        None
+    }
+}
@@ -1,54 +1,461 @@
-use std::{fs, io::Error, str::FromStr};
+use std::{fs, str::FromStr};
 
-use bevy::prelude::KeyCode;
-use kdl::{KdlDocument, KdlError, KdlNode};
+use bevy::prelude::{GamepadButtonType, KeyCode};
+use kdl::{KdlDocument, KdlNode, KdlValue};
+
+use super::{
+    errors::{ConfigError, ConfigErrorKind},
+    key_selector::{ButtonSelector, KeySelector},
+};
 
 pub fn trim(value: String) -> String {
     value.replace(['\"', '\\'], "")
 }
 
-#[derive(Debug)]
-pub struct InvalidPathError(pub Error);
-
-#[derive(Debug)]
-pub enum LoadError {
-    InvalidPathError(InvalidPathError),
-    KdlError(KdlError),
-}
-
-pub fn parse(path: &str) -> Result<Vec<KdlNode>, LoadError> {
+pub fn parse(path: &str) -> Result<Vec<KdlNode>, ConfigError> {
     let content = fs::read_to_string(path);
     match content {
         Ok(it) => match KdlDocument::from_str(&it) {
             Ok(doc) => Ok(doc.nodes().to_vec()),
-            Err(e) => Err(LoadError::KdlError(e)),
+            Err(e) => Err(ConfigError::file(path, ConfigErrorKind::InvalidKdl(e))),
         },
-        Err(e) => Err(LoadError::InvalidPathError(InvalidPathError(e))),
+        Err(e) => Err(ConfigError::file(path, ConfigErrorKind::InvalidPath(e))),
+    }
+}
+
+pub fn find_node<'a>(nodes: &'a [KdlNode], name: &str) -> Option<&'a KdlNode> {
+    nodes
+        .iter()
+        .find(|node| node.name().value().eq_ignore_ascii_case(name))
+}
+
+pub fn require_node<'a>(
+    nodes: &'a [KdlNode],
+    name: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Option<&'a KdlNode> {
+    let node = find_node(nodes, name);
+    if node.is_none() {
+        errors.push(ConfigError::missing_node(name));
+    }
+    node
+}
+
+pub fn child_nodes(node: &KdlNode) -> impl Iterator<Item = &KdlNode> {
+    node.children().into_iter().flat_map(|doc| doc.nodes())
+}
+
+pub fn try_nodes<'a, T>(
+    nodes: impl Iterator<Item = &'a KdlNode>,
+    errors: &mut Vec<ConfigError>,
+) -> Vec<T>
+where
+    T: TryFrom<&'a KdlNode, Error = Vec<ConfigError>>,
+{
+    nodes
+        .filter_map(|node| match T::try_from(node) {
+            Ok(it) => Some(it),
+            Err(mut node_errors) => {
+                errors.append(&mut node_errors);
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn name_entry(node: &KdlNode, errors: &mut Vec<ConfigError>) -> Option<String> {
+    let entry = node.entries().first()?;
+    match entry.value() {
+        KdlValue::RawString(it) | KdlValue::String(it) => Some(trim(it.clone())),
+        _ => {
+            errors.push(ConfigError::field(
+                node,
+                "name",
+                entry,
+                ConfigErrorKind::InvalidType { expected: "string" },
+            ));
+            None
+        }
+    }
+}
+
+pub fn string_field(node: &KdlNode, field: &str, errors: &mut Vec<ConfigError>) -> Option<String> {
+    let entry = node.get(field)?;
+    match entry.value() {
+        KdlValue::RawString(it) | KdlValue::String(it) => Some(trim(it.clone())),
+        _ => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::InvalidType { expected: "string" },
+            ));
+            None
+        }
+    }
+}
+
+pub fn integer_field(node: &KdlNode, field: &str, errors: &mut Vec<ConfigError>) -> Option<i64> {
+    let entry = node.get(field)?;
+    match entry.value() {
+        KdlValue::Base10(it) => Some(*it),
+        _ => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::InvalidType {
+                    expected: "integer",
+                },
+            ));
+            None
+        }
+    }
+}
+
+pub fn float_field(node: &KdlNode, field: &str, errors: &mut Vec<ConfigError>) -> Option<f64> {
+    let entry = node.get(field)?;
+    match entry.value() {
+        KdlValue::Base10Float(it) => Some(*it),
+        KdlValue::Base10(it) => Some(*it as f64),
+        _ => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::InvalidType { expected: "float" },
+            ));
+            None
+        }
+    }
+}
+
+pub fn bool_field(node: &KdlNode, field: &str, errors: &mut Vec<ConfigError>) -> Option<bool> {
+    let entry = node.get(field)?;
+    match entry.value() {
+        KdlValue::Bool(it) => Some(*it),
+        _ => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::InvalidType { expected: "bool" },
+            ));
+            None
+        }
+    }
+}
+
+pub fn parsed_field<T: FromStr>(
+    node: &KdlNode,
+    field: &str,
+    errors: &mut Vec<ConfigError>,
+    unknown: fn(String) -> ConfigErrorKind,
+) -> Option<T> {
+    let entry = match node.get(field) {
+        Some(it) => it,
+        _ => {
+            errors.push(ConfigError::missing_field(node, field));
+            return None;
+        }
+    };
+    let value = string_field(node, field, errors)?;
+
+    match value.parse() {
+        Ok(it) => Some(it),
+        Err(_) => {
+            errors.push(ConfigError::field(node, field, entry, unknown(value)));
+            None
+        }
+    }
+}
+
+pub fn parsed_name<T: FromStr>(
+    node: &KdlNode,
+    errors: &mut Vec<ConfigError>,
+    unknown: fn(String) -> ConfigErrorKind,
+) -> Option<T> {
+    let entry = match node.entries().first() {
+        Some(it) => it,
+        _ => {
+            errors.push(ConfigError::missing_field(node, "name"));
+            return None;
+        }
+    };
+    let value = name_entry(node, errors)?;
+
+    match value.parse() {
+        Ok(it) => Some(it),
+        Err(_) => {
+            errors.push(ConfigError::field(node, "name", entry, unknown(value)));
+            None
+        }
+    }
+}
+
+pub fn key_selector_field(
+    node: &KdlNode,
+    field: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Option<KeySelector> {
+    let entry = match node.get(field) {
+        Some(it) => it,
+        _ => {
+            errors.push(ConfigError::missing_field(node, field));
+            return None;
+        }
+    };
+    let display_code = string_field(node, field, errors)?;
+
+    match parse_key_code(&display_code) {
+        Ok(key_code) => Some(KeySelector {
+            key_code,
+            display_code,
+        }),
+        Err(_) => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::UnknownKeyCode(display_code),
+            ));
+            None
+        }
+    }
+}
+
+pub fn button_selector_field(
+    node: &KdlNode,
+    field: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Option<ButtonSelector> {
+    let entry = node.get(field)?;
+    let display_name = string_field(node, field, errors)?;
+
+    match parse_gamepad_button(&display_name) {
+        Ok(button) => Some(ButtonSelector {
+            button,
+            display_name,
+        }),
+        Err(_) => {
+            errors.push(ConfigError::field(
+                node,
+                field,
+                entry,
+                ConfigErrorKind::UnknownGamepadButton(display_name),
+            ));
+            None
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct UnknownKeyCode;
 
-#[derive(Debug)]
-pub struct ReservedKeyCode;
+// Names are the bevy `KeyCode` variants in snake case, with plain digits for the number row
+const KEY_CODES: &[(&str, KeyCode)] = &[
+    ("1", KeyCode::Key1),
+    ("2", KeyCode::Key2),
+    ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4),
+    ("5", KeyCode::Key5),
+    ("6", KeyCode::Key6),
+    ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8),
+    ("9", KeyCode::Key9),
+    ("0", KeyCode::Key0),
+    ("a", KeyCode::A),
+    ("b", KeyCode::B),
+    ("c", KeyCode::C),
+    ("d", KeyCode::D),
+    ("e", KeyCode::E),
+    ("f", KeyCode::F),
+    ("g", KeyCode::G),
+    ("h", KeyCode::H),
+    ("i", KeyCode::I),
+    ("j", KeyCode::J),
+    ("k", KeyCode::K),
+    ("l", KeyCode::L),
+    ("m", KeyCode::M),
+    ("n", KeyCode::N),
+    ("o", KeyCode::O),
+    ("p", KeyCode::P),
+    ("q", KeyCode::Q),
+    ("r", KeyCode::R),
+    ("s", KeyCode::S),
+    ("t", KeyCode::T),
+    ("u", KeyCode::U),
+    ("v", KeyCode::V),
+    ("w", KeyCode::W),
+    ("x", KeyCode::X),
+    ("y", KeyCode::Y),
+    ("z", KeyCode::Z),
+    ("escape", KeyCode::Escape),
+    ("f1", KeyCode::F1),
+    ("f2", KeyCode::F2),
+    ("f3", KeyCode::F3),
+    ("f4", KeyCode::F4),
+    ("f5", KeyCode::F5),
+    ("f6", KeyCode::F6),
+    ("f7", KeyCode::F7),
+    ("f8", KeyCode::F8),
+    ("f9", KeyCode::F9),
+    ("f10", KeyCode::F10),
+    ("f11", KeyCode::F11),
+    ("f12", KeyCode::F12),
+    ("f13", KeyCode::F13),
+    ("f14", KeyCode::F14),
+    ("f15", KeyCode::F15),
+    ("f16", KeyCode::F16),
+    ("f17", KeyCode::F17),
+    ("f18", KeyCode::F18),
+    ("f19", KeyCode::F19),
+    ("f20", KeyCode::F20),
+    ("f21", KeyCode::F21),
+    ("f22", KeyCode::F22),
+    ("f23", KeyCode::F23),
+    ("f24", KeyCode::F24),
+    ("snapshot", KeyCode::Snapshot),
+    ("scroll", KeyCode::Scroll),
+    ("pause", KeyCode::Pause),
+    ("insert", KeyCode::Insert),
+    ("home", KeyCode::Home),
+    ("delete", KeyCode::Delete),
+    ("end", KeyCode::End),
+    ("page_down", KeyCode::PageDown),
+    ("page_up", KeyCode::PageUp),
+    ("left", KeyCode::Left),
+    ("up", KeyCode::Up),
+    ("right", KeyCode::Right),
+    ("down", KeyCode::Down),
+    ("back", KeyCode::Back),
+    ("return", KeyCode::Return),
+    ("space", KeyCode::Space),
+    ("compose", KeyCode::Compose),
+    ("caret", KeyCode::Caret),
+    ("numlock", KeyCode::Numlock),
+    ("numpad0", KeyCode::Numpad0),
+    ("numpad1", KeyCode::Numpad1),
+    ("numpad2", KeyCode::Numpad2),
+    ("numpad3", KeyCode::Numpad3),
+    ("numpad4", KeyCode::Numpad4),
+    ("numpad5", KeyCode::Numpad5),
+    ("numpad6", KeyCode::Numpad6),
+    ("numpad7", KeyCode::Numpad7),
+    ("numpad8", KeyCode::Numpad8),
+    ("numpad9", KeyCode::Numpad9),
+    ("abnt_c1", KeyCode::AbntC1),
+    ("abnt_c2", KeyCode::AbntC2),
+    ("numpad_add", KeyCode::NumpadAdd),
+    ("apostrophe", KeyCode::Apostrophe),
+    ("apps", KeyCode::Apps),
+    ("asterisk", KeyCode::Asterisk),
+    ("plus", KeyCode::Plus),
+    ("at", KeyCode::At),
+    ("ax", KeyCode::Ax),
+    ("backslash", KeyCode::Backslash),
+    ("calculator", KeyCode::Calculator),
+    ("capital", KeyCode::Capital),
+    ("colon", KeyCode::Colon),
+    ("comma", KeyCode::Comma),
+    ("convert", KeyCode::Convert),
+    ("numpad_decimal", KeyCode::NumpadDecimal),
+    ("numpad_divide", KeyCode::NumpadDivide),
+    ("equals", KeyCode::Equals),
+    ("grave", KeyCode::Grave),
+    ("kana", KeyCode::Kana),
+    ("kanji", KeyCode::Kanji),
+    ("lalt", KeyCode::LAlt),
+    ("lbracket", KeyCode::LBracket),
+    ("lcontrol", KeyCode::LControl),
+    ("lshift", KeyCode::LShift),
+    ("lwin", KeyCode::LWin),
+    ("mail", KeyCode::Mail),
+    ("media_select", KeyCode::MediaSelect),
+    ("media_stop", KeyCode::MediaStop),
+    ("minus", KeyCode::Minus),
+    ("numpad_multiply", KeyCode::NumpadMultiply),
+    ("mute", KeyCode::Mute),
+    ("my_computer", KeyCode::MyComputer),
+    ("navigate_forward", KeyCode::NavigateForward),
+    ("navigate_backward", KeyCode::NavigateBackward),
+    ("next_track", KeyCode::NextTrack),
+    ("no_convert", KeyCode::NoConvert),
+    ("numpad_comma", KeyCode::NumpadComma),
+    ("numpad_enter", KeyCode::NumpadEnter),
+    ("numpad_equals", KeyCode::NumpadEquals),
+    ("oem102", KeyCode::Oem102),
+    ("period", KeyCode::Period),
+    ("play_pause", KeyCode::PlayPause),
+    ("power", KeyCode::Power),
+    ("prev_track", KeyCode::PrevTrack),
+    ("ralt", KeyCode::RAlt),
+    ("rbracket", KeyCode::RBracket),
+    ("rcontrol", KeyCode::RControl),
+    ("rshift", KeyCode::RShift),
+    ("rwin", KeyCode::RWin),
+    ("semicolon", KeyCode::Semicolon),
+    ("slash", KeyCode::Slash),
+    ("sleep", KeyCode::Sleep),
+    ("stop", KeyCode::Stop),
+    ("numpad_subtract", KeyCode::NumpadSubtract),
+    ("sysrq", KeyCode::Sysrq),
+    ("tab", KeyCode::Tab),
+    ("underline", KeyCode::Underline),
+    ("unlabeled", KeyCode::Unlabeled),
+    ("volume_down", KeyCode::VolumeDown),
+    ("volume_up", KeyCode::VolumeUp),
+    ("wake", KeyCode::Wake),
+    ("web_back", KeyCode::WebBack),
+    ("web_favorites", KeyCode::WebFavorites),
+    ("web_forward", KeyCode::WebForward),
+    ("web_home", KeyCode::WebHome),
+    ("web_refresh", KeyCode::WebRefresh),
+    ("web_search", KeyCode::WebSearch),
+    ("web_stop", KeyCode::WebStop),
+    ("yen", KeyCode::Yen),
+    ("copy", KeyCode::Copy),
+    ("paste", KeyCode::Paste),
+    ("cut", KeyCode::Cut),
+];
+
+pub fn parse_key_code(code: &str) -> Result<KeyCode, UnknownKeyCode> {
+    KEY_CODES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(code))
+        .map(|(_, key_code)| *key_code)
+        .ok_or(UnknownKeyCode)
+}
 
 #[derive(Debug)]
-pub enum ParseKeyCodeError {
-    UnknownKeyCode(UnknownKeyCode),
-    ReservedKeyCode(ReservedKeyCode),
-}
-
-pub fn parse_key_code(code: &str) -> Result<KeyCode, ParseKeyCodeError> {
-    match code {
-        "a" => Ok(KeyCode::A),
-        "h" => Ok(KeyCode::H),
-        "k" => Ok(KeyCode::K),
-        "m" => Ok(KeyCode::M),
-        "p" => Ok(KeyCode::P),
-        "t" => Ok(KeyCode::T),
-        "s" => Ok(KeyCode::S),
-        "i" => Err(ParseKeyCodeError::ReservedKeyCode(ReservedKeyCode)),
-        _ => Err(ParseKeyCodeError::UnknownKeyCode(UnknownKeyCode)),
-    }
+pub struct UnknownGamepadButton;
+
+const GAMEPAD_BUTTONS: &[(&str, GamepadButtonType)] = &[
+    ("south", GamepadButtonType::South),
+    ("east", GamepadButtonType::East),
+    ("north", GamepadButtonType::North),
+    ("west", GamepadButtonType::West),
+    ("c", GamepadButtonType::C),
+    ("z", GamepadButtonType::Z),
+    ("left_trigger", GamepadButtonType::LeftTrigger),
+    ("left_trigger2", GamepadButtonType::LeftTrigger2),
+    ("right_trigger", GamepadButtonType::RightTrigger),
+    ("right_trigger2", GamepadButtonType::RightTrigger2),
+    ("select", GamepadButtonType::Select),
+    ("start", GamepadButtonType::Start),
+    ("mode", GamepadButtonType::Mode),
+    ("left_thumb", GamepadButtonType::LeftThumb),
+    ("right_thumb", GamepadButtonType::RightThumb),
+    ("dpad_up", GamepadButtonType::DPadUp),
+    ("dpad_down", GamepadButtonType::DPadDown),
+    ("dpad_left", GamepadButtonType::DPadLeft),
+    ("dpad_right", GamepadButtonType::DPadRight),
+];
+
+pub fn parse_gamepad_button(name: &str) -> Result<GamepadButtonType, UnknownGamepadButton> {
+    GAMEPAD_BUTTONS
+        .iter()
+        .find(|(button_name, _)| button_name.eq_ignore_ascii_case(name))
+        .map(|(_, button)| *button)
+        .ok_or(UnknownGamepadButton)
 }
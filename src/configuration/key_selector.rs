@@ -1,7 +1,13 @@
-use bevy::prelude::KeyCode;
+use bevy::prelude::{GamepadButtonType, KeyCode};
 
 #[derive(Clone)]
 pub struct KeySelector {
     pub key_code: KeyCode,
     pub display_code: String,
 }
+
+#[derive(Clone)]
+pub struct ButtonSelector {
+    pub button: GamepadButtonType,
+    pub display_name: String,
+}
@@ -0,0 +1,253 @@
+use std::str::FromStr;
+
+use kdl::KdlNode;
+use rand::Rng;
+use tdlg::map::cells::LayerType;
+
+use super::{
+    errors::{ConfigError, ConfigErrorKind},
+    kdl_utils::{bool_field, child_nodes, integer_field, name_entry, try_nodes},
+};
+
+const DEFAULT_WEIGHT: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapLayer {
+    Floor,
+    RoomFloor,
+    Door,
+    RoomWall,
+    OuterWall,
+    Rubble,
+    Table,
+    Note,
+    CommonItem,
+}
+
+impl MapLayer {
+    pub fn from_layer_type(layer: &LayerType) -> Option<Self> {
+        match *layer {
+            LayerType::Floor => Some(Self::Floor),
+            LayerType::RoomFloor => Some(Self::RoomFloor),
+            LayerType::Door => Some(Self::Door),
+            LayerType::RoomWall => Some(Self::RoomWall),
+            LayerType::OuterWall => Some(Self::OuterWall),
+            LayerType::Rubble => Some(Self::Rubble),
+            LayerType::Table => Some(Self::Table),
+            LayerType::Note => Some(Self::Note),
+            LayerType::CommonItem => Some(Self::CommonItem),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseMapLayerError;
+
+impl FromStr for MapLayer {
+    type Err = ParseMapLayerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "floor" => Ok(Self::Floor),
+            "room_floor" => Ok(Self::RoomFloor),
+            "door" => Ok(Self::Door),
+            "room_wall" => Ok(Self::RoomWall),
+            "outer_wall" => Ok(Self::OuterWall),
+            "rubble" => Ok(Self::Rubble),
+            "table" => Ok(Self::Table),
+            "note" => Ok(Self::Note),
+            "common_item" => Ok(Self::CommonItem),
+            _ => Err(ParseMapLayerError),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayerContent {
+    Floor(String),
+    Structure(String),
+    Tool(String),
+    Item(String),
+    Note,
+    RandomItem,
+}
+
+#[derive(PartialEq)]
+pub struct LayerEntry {
+    pub content: LayerContent,
+    pub weight: u32,
+    pub guaranteed: bool,
+}
+
+impl TryFrom<&KdlNode> for LayerEntry {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let key = name_entry(node, &mut errors);
+        let weight =
+            integer_field(node, "weight", &mut errors).map_or(DEFAULT_WEIGHT, |it| it as u32);
+        let guaranteed = bool_field(node, "guaranteed", &mut errors).unwrap_or(false);
+
+        let content = match (node.name().value(), key) {
+            ("floor", Some(key)) => Some(LayerContent::Floor(key)),
+            ("structure", Some(key)) => Some(LayerContent::Structure(key)),
+            ("tool", Some(key)) => Some(LayerContent::Tool(key)),
+            ("item", Some(key)) => Some(LayerContent::Item(key)),
+            ("floor" | "structure" | "tool" | "item", None) => {
+                errors.push(ConfigError::missing_field(node, "key"));
+                None
+            }
+            ("note", _) => Some(LayerContent::Note),
+            ("random_item", _) => Some(LayerContent::RandomItem),
+            (name, _) => {
+                errors.push(ConfigError::node(
+                    node,
+                    ConfigErrorKind::UnknownLayerContent(name.to_string()),
+                ));
+                None
+            }
+        };
+
+        match content {
+            Some(content) if errors.is_empty() => Ok(Self {
+                content,
+                weight,
+                guaranteed,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct LayerMapping {
+    pub layer: MapLayer,
+    pub entries: Vec<LayerEntry>,
+}
+
+impl TryFrom<&KdlNode> for LayerMapping {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let layer = match node.entries().first() {
+            Some(entry) => {
+                let name = name_entry(node, &mut errors).unwrap_or_default();
+                match name.parse() {
+                    Ok(it) => Some(it),
+                    Err(_) => {
+                        errors.push(ConfigError::field(
+                            node,
+                            "name",
+                            entry,
+                            ConfigErrorKind::UnknownLayerType(name),
+                        ));
+                        None
+                    }
+                }
+            }
+            _ => {
+                errors.push(ConfigError::missing_field(node, "name"));
+                None
+            }
+        };
+        let entries = try_nodes(child_nodes(node), &mut errors);
+
+        match layer {
+            Some(layer) if errors.is_empty() => Ok(Self { layer, entries }),
+            _ => Err(errors),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub struct LayerMappings {
+    pub mappings: Vec<LayerMapping>,
+}
+
+impl LayerMappings {
+    pub fn mapping(&self, layer: MapLayer) -> Option<&LayerMapping> {
+        self.mappings.iter().find(|mapping| mapping.layer == layer)
+    }
+
+    pub fn contents(&self) -> impl Iterator<Item = (MapLayer, &LayerContent)> {
+        self.mappings.iter().flat_map(|mapping| {
+            mapping
+                .entries
+                .iter()
+                .map(move |entry| (mapping.layer, &entry.content))
+        })
+    }
+}
+
+impl TryFrom<&KdlNode> for LayerMappings {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let mappings = try_nodes(
+            child_nodes(node).filter(|child| child.name().value() == "layer"),
+            &mut errors,
+        );
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self { mappings })
+    }
+}
+
+// Guaranteed entries are spawned once each per floor before any weighted picks
+#[derive(Default)]
+pub struct LayerPicker {
+    spawned_guaranteed: Vec<(MapLayer, usize)>,
+}
+
+impl LayerPicker {
+    pub fn pick<'a>(
+        &mut self,
+        mapping: &'a LayerMapping,
+        allow_guaranteed: bool,
+        rng: &mut impl Rng,
+    ) -> Option<&'a LayerContent> {
+        if allow_guaranteed {
+            let next_guaranteed = mapping
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.guaranteed)
+                .find(|(index, _)| !self.spawned_guaranteed.contains(&(mapping.layer, *index)));
+
+            if let Some((index, entry)) = next_guaranteed {
+                self.spawned_guaranteed.push((mapping.layer, index));
+                return Some(&entry.content);
+            }
+        }
+
+        let total: u32 = mapping
+            .entries
+            .iter()
+            .filter(|entry| !entry.guaranteed)
+            .map(|entry| entry.weight)
+            .sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for entry in mapping.entries.iter().filter(|entry| !entry.guaranteed) {
+            if roll < entry.weight {
+                return Some(&entry.content);
+            }
+            roll -= entry.weight;
+        }
+
+        None
+    }
+}
@@ -0,0 +1,75 @@
+use kdl::KdlNode;
+use rand::{seq::SliceRandom, Rng};
+
+use super::{
+    errors::{in_file, ConfigError},
+    kdl_utils::{child_nodes, name_entry, parse, string_field, try_nodes},
+};
+
+#[derive(Clone)]
+pub struct NoteConfiguration {
+    pub key: String,
+    pub title: String,
+    pub text: String,
+    sprite: String,
+    pub sprite_index: Option<usize>,
+}
+
+impl NoteConfiguration {
+    pub fn sprite_location(&self) -> &str {
+        &self.sprite[..]
+    }
+}
+
+impl TryFrom<&KdlNode> for NoteConfiguration {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let title = name_entry(node, &mut errors).unwrap_or_default();
+        let key = string_field(node, "key", &mut errors).unwrap_or_default();
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+        let lines: Vec<String> = child_nodes(node)
+            .filter_map(|line_node| name_entry(line_node, &mut errors))
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self {
+            key,
+            title,
+            text: lines.join("\n"),
+            sprite,
+            sprite_index: None,
+        })
+    }
+}
+
+pub struct NotesConfig {
+    pub configurations: Vec<NoteConfiguration>,
+}
+
+impl NotesConfig {
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let note_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let configurations = try_nodes(note_nodes.iter(), &mut errors);
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
+
+        Ok(Self { configurations })
+    }
+
+    pub fn config_by_key(&self, key: &str) -> Option<&NoteConfiguration> {
+        self.configurations.iter().find(|config| config.key == key)
+    }
+
+    pub fn pick(&self, rng: &mut impl Rng) -> Option<&NoteConfiguration> {
+        self.configurations.choose(rng)
+    }
+}
@@ -1,20 +1,29 @@
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 
-use super::kdl_utils::parse;
+use super::{
+    errors::{in_file, keep_errors, ConfigError},
+    kdl_utils::{
+        child_nodes, find_node, integer_field, parse, require_node, string_field, try_nodes,
+    },
+};
 
 pub struct PlayerSpriteConfigs {
     pub options: Vec<PlayerSpriteConfig>,
 }
 
-impl From<&KdlNode> for PlayerSpriteConfigs {
-    fn from(node: &KdlNode) -> Self {
-        let options = node
-            .children()
-            .iter()
-            .flat_map(|doc| doc.nodes())
-            .map(PlayerSpriteConfig::from)
-            .collect();
-        Self { options }
+impl TryFrom<&KdlNode> for PlayerSpriteConfigs {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let options = try_nodes(child_nodes(node), &mut errors);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self { options })
     }
 }
 
@@ -23,14 +32,16 @@ pub struct PlayerSpriteConfig {
     pub sprite_index: Option<usize>,
 }
 
-impl From<&KdlNode> for PlayerSpriteConfig {
-    fn from(node: &KdlNode) -> Self {
-        let file_config = SpriteFileConfig::from(node);
+impl TryFrom<&KdlNode> for PlayerSpriteConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let file_config = SpriteFileConfig::try_from(node)?;
 
-        Self {
+        Ok(Self {
             file_config,
             sprite_index: None,
-        }
+        })
     }
 }
 
@@ -44,19 +55,19 @@ pub struct SpriteFileConfig {
     pub sprite: String,
 }
 
-impl From<&KdlNode> for SpriteFileConfig {
-    fn from(node: &KdlNode) -> Self {
-        let sprite = match node.get("sprite") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        Self { sprite }
+impl TryFrom<&KdlNode> for SpriteFileConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self { sprite })
     }
 }
 
@@ -64,47 +75,52 @@ pub struct PlayerInfo {
     pub name: String,
 }
 
-impl From<&KdlNode> for PlayerInfo {
-    fn from(node: &KdlNode) -> Self {
-        let name = match node.get("name") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        Self { name }
+impl TryFrom<&KdlNode> for PlayerInfo {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let name = string_field(node, "name", &mut errors).unwrap_or_default();
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self { name })
     }
 }
 
+const DEFAULT_SIGHT_RADIUS: i32 = 8;
+
 pub struct PlayerConfig {
     pub info: PlayerInfo,
     pub sprite_configs: PlayerSpriteConfigs,
+    pub sight_radius: i32,
 }
 
 impl PlayerConfig {
-    pub fn load(path: &str) -> Self {
-        let player_nodes = parse(path).unwrap();
-
-        let info_node = player_nodes
-            .iter()
-            .find(|node| node.name().value().eq_ignore_ascii_case("info"))
-            .unwrap();
-        let info = PlayerInfo::from(info_node);
-
-        let sprites_node = player_nodes
-            .iter()
-            .find(|node| node.name().value().eq_ignore_ascii_case("sprites"))
-            .unwrap();
-
-        let sprite_configs = PlayerSpriteConfigs::from(sprites_node);
-
-        Self {
-            info,
-            sprite_configs,
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let player_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let info = require_node(&player_nodes, "info", &mut errors)
+            .and_then(|node| keep_errors(PlayerInfo::try_from(node), &mut errors));
+
+        let sprite_configs = require_node(&player_nodes, "sprites", &mut errors)
+            .and_then(|node| keep_errors(PlayerSpriteConfigs::try_from(node), &mut errors));
+
+        let sight_radius = find_node(&player_nodes, "sight")
+            .and_then(|node| integer_field(node, "radius", &mut errors))
+            .map_or(DEFAULT_SIGHT_RADIUS, |it| it as i32);
+
+        match (info, sprite_configs) {
+            (Some(info), Some(sprite_configs)) if errors.is_empty() => Ok(Self {
+                info,
+                sprite_configs,
+                sight_radius,
+            }),
+            _ => Err(in_file(errors, path)),
         }
     }
 }
@@ -1,8 +1,14 @@
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 
 use crate::components::structure::StructureType;
 
-use super::kdl_utils::parse;
+use super::{
+    errors::{in_file, ConfigError, ConfigErrorKind},
+    kdl_utils::{
+        bool_field, child_nodes, integer_field, name_entry, parse, parsed_field, string_field,
+        try_nodes,
+    },
+};
 
 #[derive(Clone)]
 pub struct StructureConfig {
@@ -46,81 +52,50 @@ impl StructureHealthConfig {
     }
 }
 
-impl From<&KdlNode> for StructureHealthConfig {
-    fn from(node: &KdlNode) -> Self {
-        let file_config = StructureHealthFileConfig::from(node);
-        Self {
+impl TryFrom<&KdlNode> for StructureHealthConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let file_config = StructureHealthFileConfig::try_from(node)?;
+        Ok(Self {
             file_config,
             sprite_index: None,
-        }
+        })
     }
 }
 
 const DEFAULT_MAX_HEALTH: i32 = 10;
 const DEFAULT_MIN_HEALTH: i32 = 1;
 
-impl From<&KdlNode> for StructureConfig {
-    fn from(node: &KdlNode) -> Self {
-        let name = match node.entries().get(0) {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let key = match node.get("key") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let structure_type = match node.get("type") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let starting_health = match node.get("health") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as i32,
-                _ => DEFAULT_MAX_HEALTH,
-            },
-            _ => DEFAULT_MAX_HEALTH,
-        };
-
-        let initial_visible: bool = match node.get("visible") {
-            Some(entry) => match entry.value() {
-                KdlValue::Bool(it) => *it,
-                _ => true,
-            },
-            _ => true,
-        };
-
-        let health_configs = node
-            .children()
-            .iter()
-            .flat_map(|doc| doc.nodes())
-            .map(StructureHealthConfig::from)
-            .collect();
-
-        Self {
-            health_configs,
-            initial_visible,
-            key,
-            name,
-            starting_health,
-            structure_type: structure_type.parse().unwrap(),
+impl TryFrom<&KdlNode> for StructureConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let name = name_entry(node, &mut errors).unwrap_or_default();
+        let key = string_field(node, "key", &mut errors).unwrap_or_default();
+        let structure_type = parsed_field(
+            node,
+            "type",
+            &mut errors,
+            ConfigErrorKind::UnknownStructureType,
+        );
+        let starting_health =
+            integer_field(node, "health", &mut errors).map_or(DEFAULT_MAX_HEALTH, |it| it as i32);
+        let initial_visible = bool_field(node, "visible", &mut errors).unwrap_or(true);
+        let health_configs = try_nodes(child_nodes(node), &mut errors);
+
+        match structure_type {
+            Some(structure_type) if errors.is_empty() => Ok(Self {
+                health_configs,
+                initial_visible,
+                key,
+                name,
+                starting_health,
+                structure_type,
+            }),
+            _ => Err(errors),
         }
     }
 }
@@ -135,66 +110,33 @@ pub struct StructureHealthFileConfig {
     pub can_be_cleared: bool,
 }
 
-impl From<&KdlNode> for StructureHealthFileConfig {
-    fn from(node: &KdlNode) -> Self {
-        let sprite = match node.get("sprite") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let max_health = match node.get("max_health") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as i32,
-                _ => DEFAULT_MAX_HEALTH,
-            },
-            _ => DEFAULT_MAX_HEALTH,
-        };
-
-        let min_health = match node.get("min_health") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as i32,
-                _ => DEFAULT_MIN_HEALTH,
-            },
-            _ => DEFAULT_MIN_HEALTH,
-        };
-
-        let can_be_broken = match node.get("can_be_broken") {
-            Some(entry) => match entry.value() {
-                KdlValue::Bool(it) => *it,
-                _ => false,
-            },
-            _ => false,
-        };
-
-        let can_be_walked_on = match node.get("can_be_walked_on") {
-            Some(entry) => match entry.value() {
-                KdlValue::Bool(it) => *it,
-                _ => false,
-            },
-            _ => false,
-        };
-
-        let can_be_cleared = match node.get("can_be_cleared") {
-            Some(entry) => match entry.value() {
-                KdlValue::Bool(it) => *it,
-                _ => false,
-            },
-            _ => false,
-        };
-
-        Self {
+impl TryFrom<&KdlNode> for StructureHealthFileConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+        let max_health = integer_field(node, "max_health", &mut errors)
+            .map_or(DEFAULT_MAX_HEALTH, |it| it as i32);
+        let min_health = integer_field(node, "min_health", &mut errors)
+            .map_or(DEFAULT_MIN_HEALTH, |it| it as i32);
+        let can_be_broken = bool_field(node, "can_be_broken", &mut errors).unwrap_or(false);
+        let can_be_walked_on = bool_field(node, "can_be_walked_on", &mut errors).unwrap_or(false);
+        let can_be_cleared = bool_field(node, "can_be_cleared", &mut errors).unwrap_or(false);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Self {
             can_be_broken,
             can_be_walked_on,
             can_be_cleared,
             max_health,
             min_health,
             sprite_location: sprite,
-        }
+        })
     }
 }
 
@@ -207,10 +149,15 @@ impl StructuresConfig {
         self.configurations.iter().find(|c| c.key == key)
     }
 
-    pub fn load(path: &str) -> Self {
-        let structure_nodes = parse(path).unwrap();
-        let configurations: Vec<StructureConfig> =
-            structure_nodes.iter().map(StructureConfig::from).collect();
-        Self { configurations }
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let structure_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let configurations = try_nodes(structure_nodes.iter(), &mut errors);
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
+
+        Ok(Self { configurations })
     }
 }
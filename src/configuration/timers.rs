@@ -1,7 +1,12 @@
 use bevy::time::Timer;
 
 pub const CONSTANT_MOVE_WAIT_TIME: f32 = 0.2;
+pub const CONFIG_RELOAD_WAIT_TIME: f32 = 1.0;
 
-pub fn movement_timer() -> Timer {
-    Timer::from_seconds(CONSTANT_MOVE_WAIT_TIME, bevy::time::TimerMode::Repeating)
+pub fn movement_timer(repeat_delay: f32) -> Timer {
+    Timer::from_seconds(repeat_delay, bevy::time::TimerMode::Repeating)
+}
+
+pub fn config_reload_timer() -> Timer {
+    Timer::from_seconds(CONFIG_RELOAD_WAIT_TIME, bevy::time::TimerMode::Repeating)
 }
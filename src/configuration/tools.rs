@@ -1,4 +1,4 @@
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 
 use crate::components::{
     damage::Damage,
@@ -7,7 +7,11 @@ use crate::components::{
 };
 
 use super::{
-    kdl_utils::{parse, parse_key_code},
+    errors::{in_file, ConfigError, ConfigErrorKind},
+    kdl_utils::{
+        bool_field, integer_field, key_selector_field, name_entry, parse, parsed_field,
+        string_field, try_nodes,
+    },
     key_selector::KeySelector,
 };
 
@@ -18,99 +22,41 @@ pub struct ToolFileConfig {
     tool_type: ToolType,
     min_damage: i32,
     max_damage: i32,
+    water: u32,
     starter: bool,
     sprite: String,
     inventory_selector: KeySelector,
 }
 
-impl From<&KdlNode> for ToolFileConfig {
-    fn from(node: &KdlNode) -> Self {
-        let name = match node.entries().get(0) {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let key = match node.get("key") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let key_code = match node.get("key_code") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let starter = match node.get("starter") {
-            Some(entry) => match entry.value() {
-                KdlValue::Bool(it) => *it,
-                _ => false,
-            },
-            _ => false,
-        };
-
-        let min_damage = match node.get("min_damage") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as i32,
-                _ => 0,
-            },
-            _ => 0,
-        };
-
-        let max_damage = match node.get("max_damage") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as i32,
-                _ => 0,
-            },
-            _ => 0,
-        };
-
-        let tool_type = match node.get("type") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        let sprite = match node.get("sprite") {
-            Some(entry) => match entry.value() {
-                KdlValue::RawString(it) | KdlValue::String(it) => {
-                    super::kdl_utils::trim(it.clone())
-                }
-                _ => "".to_string(),
-            },
-            _ => "".to_string(),
-        };
-
-        Self {
-            key,
-            max_damage,
-            min_damage,
-            name,
-            sprite,
-            starter,
-            tool_type: tool_type.parse().unwrap(),
-            inventory_selector: KeySelector {
-                key_code: parse_key_code(&key_code).unwrap(),
-                display_code: key_code.clone(),
-            },
+impl TryFrom<&KdlNode> for ToolFileConfig {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let name = name_entry(node, &mut errors).unwrap_or_default();
+        let key = string_field(node, "key", &mut errors).unwrap_or_default();
+        let inventory_selector = key_selector_field(node, "key_code", &mut errors);
+        let starter = bool_field(node, "starter", &mut errors).unwrap_or(false);
+        let min_damage = integer_field(node, "min_damage", &mut errors).map_or(0, |it| it as i32);
+        let max_damage = integer_field(node, "max_damage", &mut errors).map_or(0, |it| it as i32);
+        let water = integer_field(node, "water", &mut errors).map_or(0, |it| it as u32);
+        let tool_type = parsed_field(node, "type", &mut errors, ConfigErrorKind::UnknownToolType);
+        let sprite = string_field(node, "sprite", &mut errors).unwrap_or_default();
+
+        match (tool_type, inventory_selector) {
+            (Some(tool_type), Some(inventory_selector)) if errors.is_empty() => Ok(Self {
+                key,
+                max_damage,
+                min_damage,
+                water,
+                name,
+                sprite,
+                starter,
+                tool_type,
+                inventory_selector,
+            }),
+            _ => Err(errors),
         }
     }
 }
@@ -134,6 +80,11 @@ impl ToolConfiguration {
         self.file_config.max_damage
     }
 
+    // Percent of soil moisture one use of a watering can restores
+    pub fn water(&self) -> u32 {
+        self.file_config.water
+    }
+
     pub fn name(&self) -> &String {
         &self.file_config.name
     }
@@ -165,18 +116,21 @@ impl ToolConfiguration {
                 max_damage: self.max_damage(),
                 min_damage: self.min_damage(),
             }),
+            water: self.water(),
         }
     }
 }
 
-impl From<&KdlNode> for ToolConfiguration {
-    fn from(node: &KdlNode) -> Self {
-        let file_config = ToolFileConfig::from(node);
+impl TryFrom<&KdlNode> for ToolConfiguration {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let file_config = ToolFileConfig::try_from(node)?;
 
-        Self {
+        Ok(Self {
             file_config,
             sprite_index: None,
-        }
+        })
     }
 }
 
@@ -185,19 +139,24 @@ pub struct ToolConfigurations {
 }
 
 impl ToolConfigurations {
-    pub fn load(path: &str) -> Self {
-        let tool_nodes = parse(path).unwrap();
-        let configurations = tool_nodes.iter().map(ToolConfiguration::from).collect();
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let tool_nodes = parse(path).map_err(|e| vec![e])?;
+
+        let mut errors = Vec::new();
+        let configurations = try_nodes(tool_nodes.iter(), &mut errors);
+        if !errors.is_empty() {
+            return Err(in_file(errors, path));
+        }
 
-        Self { configurations }
+        Ok(Self { configurations })
     }
 }
 
 impl ToolConfigurations {
-    pub fn tool_by_type(&self, tool_type: ToolType) -> Option<ToolConfiguration> {
+    pub fn tool_by_key(&self, key: &str) -> Option<ToolConfiguration> {
         self.configurations
             .iter()
-            .find(|config| config.tool_type() == tool_type)
+            .find(|config| config.key() == key)
             .cloned()
     }
 }
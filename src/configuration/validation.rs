@@ -0,0 +1,789 @@
+use std::{fmt, path::Path};
+
+use crate::{components::item::ItemKind, states::AppState};
+
+use super::{
+    controls::InputAction,
+    game::GameConfiguration,
+    key_selector::{ButtonSelector, KeySelector},
+    layers::{LayerContent, MapLayer},
+    structures::StructureConfig,
+};
+
+// Structures the game spawns by key rather than through a map layer
+const REQUIRED_STRUCTURES: [&str; 4] = ["exit", "ladder", "tilled_soil", "dead_plant"];
+
+#[derive(Debug)]
+pub enum ValidationError {
+    MissingSprite {
+        owner: String,
+        sprite: String,
+    },
+    InvertedHealthRange {
+        structure: String,
+        min_health: i32,
+        max_health: i32,
+    },
+    OverlappingHealthRanges {
+        structure: String,
+        first: (i32, i32),
+        second: (i32, i32),
+    },
+    HealthRangeGap {
+        structure: String,
+        from: i32,
+        to: i32,
+    },
+    UncoveredStartingHealth {
+        structure: String,
+        health: i32,
+    },
+    DuplicateKey {
+        kind: &'static str,
+        key: String,
+    },
+    DuplicateKeyCode {
+        key_code: String,
+        first: String,
+        second: String,
+    },
+    MissingRequiredStructure {
+        key: &'static str,
+    },
+    MissingItemCrop {
+        item: String,
+    },
+    UnknownItemCrop {
+        item: String,
+        crop: String,
+    },
+    UnknownLayerKey {
+        layer: MapLayer,
+        kind: &'static str,
+        key: String,
+    },
+    DuplicateButton {
+        button: String,
+        first: InputAction,
+        second: InputAction,
+    },
+    UnboundAction {
+        action: InputAction,
+    },
+    InvalidRepeatDelay {
+        delay: f32,
+    },
+    InvalidStickThreshold {
+        threshold: f32,
+    },
+    ZeroDepthBand {
+        to: usize,
+    },
+    InvertedDepthBand {
+        from: usize,
+        to: usize,
+    },
+    OverlappingDepthBands {
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+    InvalidStructureHealthScale {
+        from: usize,
+        scale: f32,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSprite { owner, sprite } => {
+                write!(f, "{owner} uses sprite \"{sprite}\" which does not exist")
+            }
+            Self::InvertedHealthRange {
+                structure,
+                min_health,
+                max_health,
+            } => write!(
+                f,
+                "structure `{structure}` has min_health {min_health} above max_health {max_health}"
+            ),
+            Self::OverlappingHealthRanges {
+                structure,
+                first,
+                second,
+            } => write!(
+                f,
+                "structure `{structure}` has overlapping health ranges {}..={} and {}..={}",
+                first.0, first.1, second.0, second.1
+            ),
+            Self::HealthRangeGap {
+                structure,
+                from,
+                to,
+            } => write!(
+                f,
+                "structure `{structure}` has no health_config covering {from}..={to}"
+            ),
+            Self::UncoveredStartingHealth { structure, health } => write!(
+                f,
+                "structure `{structure}` starts at health {health} which no health_config covers"
+            ),
+            Self::DuplicateKey { kind, key } => {
+                write!(f, "{kind} key `{key}` is used more than once")
+            }
+            Self::DuplicateKeyCode {
+                key_code,
+                first,
+                second,
+            } => write!(
+                f,
+                "key_code \"{key_code}\" is used by both `{first}` and `{second}`"
+            ),
+            Self::MissingRequiredStructure { key } => {
+                write!(f, "structure `{key}` is required but does not exist")
+            }
+            Self::MissingItemCrop { item } => {
+                write!(f, "item `{item}` needs a crop for its kind")
+            }
+            Self::UnknownItemCrop { item, crop } => {
+                write!(f, "item `{item}` uses crop `{crop}` which does not exist")
+            }
+            Self::UnknownLayerKey { layer, kind, key } => {
+                write!(
+                    f,
+                    "layer {layer:?} spawns {kind} `{key}` which does not exist"
+                )
+            }
+            Self::DuplicateButton {
+                button,
+                first,
+                second,
+            } => write!(
+                f,
+                "gamepad button \"{button}\" is used by both `{first}` and `{second}`"
+            ),
+            Self::UnboundAction { action } => {
+                write!(f, "action `{action}` has no key or button bound to it")
+            }
+            Self::InvalidRepeatDelay { delay } => {
+                write!(f, "movement repeat_delay {delay} must be above 0")
+            }
+            Self::InvalidStickThreshold { threshold } => {
+                write!(
+                    f,
+                    "gamepad stick_threshold {threshold} must be between 0 and 1"
+                )
+            }
+            Self::ZeroDepthBand { to } => {
+                write!(
+                    f,
+                    "depth band 0..={to} starts at 0 but the first floor is depth 1"
+                )
+            }
+            Self::InvertedDepthBand { from, to } => {
+                write!(f, "depth band {from}..={to} ends before it starts")
+            }
+            Self::OverlappingDepthBands { first, second } => write!(
+                f,
+                "depth bands {}..={} and {}..={} overlap",
+                first.0, first.1, second.0, second.1
+            ),
+            Self::InvalidStructureHealthScale { from, scale } => write!(
+                f,
+                "depth band starting at {from} has structure_health {scale} which must be above 0"
+            ),
+        }
+    }
+}
+
+pub fn validate(config: &GameConfiguration, asset_path: &str) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    check_sprites(config, asset_path, &mut errors);
+
+    for structure in config.structures_config.configurations.iter() {
+        check_health_ranges(structure, &mut errors);
+    }
+
+    check_duplicate_keys(config, &mut errors);
+    check_duplicate_key_codes(config, &mut errors);
+    check_required_structures(config, &mut errors);
+    check_controls(config, &mut errors);
+    check_item_crops(config, &mut errors);
+    check_layer_keys(config, &mut errors);
+    check_depth_bands(config, &mut errors);
+
+    errors
+}
+
+fn check_sprites(config: &GameConfiguration, asset_path: &str, errors: &mut Vec<ValidationError>) {
+    let mut sprites: Vec<(String, &str)> = Vec::new();
+
+    for crop in config.crops_config.configurations.iter() {
+        for stage in crop.stages.iter() {
+            sprites.push((format!("crop `{}`", crop.key), stage.sprite_location()));
+        }
+        sprites.push((
+            format!("crop `{}`", crop.key),
+            crop.withered.sprite_location(),
+        ));
+    }
+
+    for structure in config.structures_config.configurations.iter() {
+        for health_config in structure.health_configs.iter() {
+            sprites.push((
+                format!("structure `{}`", structure.key),
+                health_config.sprite_location(),
+            ));
+        }
+    }
+
+    for floor in config.floors_config.configurations.iter() {
+        for option in floor.sprite_options.iter() {
+            sprites.push((format!("floor `{}`", floor.key), option.sprite_location()));
+        }
+    }
+
+    for option in config.player_config.sprite_configs.options.iter() {
+        sprites.push(("player".to_string(), option.sprite_location()));
+    }
+
+    for tool in config.tool_configs.configurations.iter() {
+        sprites.push((format!("tool `{}`", tool.key()), tool.sprite_location()));
+    }
+
+    for item in config.items_config.configurations.iter() {
+        sprites.push((format!("item `{}`", item.key()), item.sprite_location()));
+    }
+
+    for note in config.notes_config.configurations.iter() {
+        sprites.push((format!("note `{}`", note.key), note.sprite_location()));
+    }
+
+    for (owner, sprite) in sprites {
+        if !Path::new(asset_path).join(sprite).is_file() {
+            errors.push(ValidationError::MissingSprite {
+                owner,
+                sprite: sprite.to_string(),
+            });
+        }
+    }
+}
+
+fn check_health_ranges(structure: &StructureConfig, errors: &mut Vec<ValidationError>) {
+    let mut ranges: Vec<(i32, i32)> = Vec::new();
+
+    for health_config in structure.health_configs.iter() {
+        if health_config.min_health() > health_config.max_health() {
+            errors.push(ValidationError::InvertedHealthRange {
+                structure: structure.key.clone(),
+                min_health: health_config.min_health(),
+                max_health: health_config.max_health(),
+            });
+            continue;
+        }
+
+        ranges.push((health_config.min_health(), health_config.max_health()));
+    }
+
+    ranges.sort();
+
+    for pair in ranges.windows(2) {
+        let (first, second) = (pair[0], pair[1]);
+
+        if second.0 <= first.1 {
+            errors.push(ValidationError::OverlappingHealthRanges {
+                structure: structure.key.clone(),
+                first,
+                second,
+            });
+        } else if second.0 > first.1 + 1 {
+            errors.push(ValidationError::HealthRangeGap {
+                structure: structure.key.clone(),
+                from: first.1 + 1,
+                to: second.0 - 1,
+            });
+        }
+    }
+
+    if !ranges
+        .iter()
+        .any(|(min, max)| (*min..=*max).contains(&structure.starting_health))
+    {
+        errors.push(ValidationError::UncoveredStartingHealth {
+            structure: structure.key.clone(),
+            health: structure.starting_health,
+        });
+    }
+}
+
+fn check_duplicate_keys(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    // Crops and tools share the inventory, so their keys have to be unique together
+    let inventory_keys: Vec<&str> = config
+        .crops_config
+        .configurations
+        .iter()
+        .map(|crop| crop.key.as_str())
+        .chain(
+            config
+                .tool_configs
+                .configurations
+                .iter()
+                .map(|tool| tool.key().as_str()),
+        )
+        .collect();
+    push_duplicates("inventory", &inventory_keys, errors);
+
+    let structure_keys: Vec<&str> = config
+        .structures_config
+        .configurations
+        .iter()
+        .map(|structure| structure.key.as_str())
+        .collect();
+    push_duplicates("structure", &structure_keys, errors);
+
+    let floor_keys: Vec<&str> = config
+        .floors_config
+        .configurations
+        .iter()
+        .map(|floor| floor.key.as_str())
+        .collect();
+    push_duplicates("floor", &floor_keys, errors);
+
+    // Tools, items and notes can all lie on the ground and are saved by key
+    let item_keys: Vec<&str> = config
+        .tool_configs
+        .configurations
+        .iter()
+        .map(|tool| tool.key().as_str())
+        .chain(
+            config
+                .items_config
+                .configurations
+                .iter()
+                .map(|item| item.key().as_str()),
+        )
+        .chain(
+            config
+                .notes_config
+                .configurations
+                .iter()
+                .map(|note| note.key.as_str()),
+        )
+        .collect();
+    push_duplicates("item", &item_keys, errors);
+}
+
+fn push_duplicates(kind: &'static str, keys: &[&str], errors: &mut Vec<ValidationError>) {
+    for (index, key) in keys.iter().enumerate() {
+        let first_index = keys.iter().position(|other| other == key);
+        if first_index == Some(index) && keys[index + 1..].contains(key) {
+            errors.push(ValidationError::DuplicateKey {
+                kind,
+                key: key.to_string(),
+            });
+        }
+    }
+}
+
+// Item selectors are only read on the inventory screen, controls in the states they're used in
+fn check_duplicate_key_codes(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    const INVENTORY: &[AppState] = &[AppState::InventoryScreen];
+
+    let selectors: Vec<(&str, &KeySelector, &[AppState])> = config
+        .crops_config
+        .configurations
+        .iter()
+        .map(|crop| (crop.key.as_str(), &crop.inventory_selector, INVENTORY))
+        .chain(
+            config
+                .tool_configs
+                .configurations
+                .iter()
+                .map(|tool| (tool.key().as_str(), tool.inventory_selector(), INVENTORY)),
+        )
+        .chain(
+            config
+                .controls_config
+                .bindings
+                .iter()
+                .filter_map(|binding| {
+                    binding
+                        .key
+                        .as_ref()
+                        .map(|key| (binding.action.name(), key, binding.action.states()))
+                }),
+        )
+        .collect();
+
+    for (index, (key, selector, states)) in selectors.iter().enumerate() {
+        for (other_key, other_selector, other_states) in selectors[index + 1..].iter() {
+            if key == other_key || selector.key_code != other_selector.key_code {
+                continue;
+            }
+
+            if states.iter().any(|state| other_states.contains(state)) {
+                errors.push(ValidationError::DuplicateKeyCode {
+                    key_code: selector.display_code.clone(),
+                    first: key.to_string(),
+                    second: other_key.to_string(),
+                });
+            }
+        }
+    }
+}
+
+fn check_required_structures(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    for key in REQUIRED_STRUCTURES {
+        if config.structures_config.config_by_key(key).is_none() {
+            errors.push(ValidationError::MissingRequiredStructure { key });
+        }
+    }
+}
+
+fn check_controls(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    for action in InputAction::ALL {
+        if config.controls_config.key_codes(action).next().is_none()
+            && config.controls_config.buttons(action).next().is_none()
+        {
+            errors.push(ValidationError::UnboundAction { action });
+        }
+    }
+
+    let buttons: Vec<(InputAction, &ButtonSelector)> = config
+        .controls_config
+        .bindings
+        .iter()
+        .filter_map(|binding| {
+            binding
+                .button
+                .as_ref()
+                .map(|button| (binding.action, button))
+        })
+        .collect();
+
+    for (index, (action, selector)) in buttons.iter().enumerate() {
+        for (other_action, other_selector) in buttons[index + 1..].iter() {
+            let shares_state = action
+                .states()
+                .iter()
+                .any(|state| other_action.states().contains(state));
+
+            if action != other_action && selector.button == other_selector.button && shares_state {
+                errors.push(ValidationError::DuplicateButton {
+                    button: selector.display_name.clone(),
+                    first: *action,
+                    second: *other_action,
+                });
+            }
+        }
+    }
+
+    if config.controls_config.repeat_delay <= 0.0 {
+        errors.push(ValidationError::InvalidRepeatDelay {
+            delay: config.controls_config.repeat_delay,
+        });
+    }
+
+    // At 0 any stick drift presses every direction, at 1 the stick can never move
+    let threshold = config.controls_config.stick_threshold;
+    if threshold <= 0.0 || threshold >= 1.0 {
+        errors.push(ValidationError::InvalidStickThreshold { threshold });
+    }
+}
+
+fn check_item_crops(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    for item in config.items_config.configurations.iter() {
+        if !matches!(item.kind(), ItemKind::Seed | ItemKind::Produce) {
+            continue;
+        }
+
+        match item.crop() {
+            Some(crop) if config.crops_config.config_by_key(crop).is_none() => {
+                errors.push(ValidationError::UnknownItemCrop {
+                    item: item.key().clone(),
+                    crop: crop.to_string(),
+                });
+            }
+            None => errors.push(ValidationError::MissingItemCrop {
+                item: item.key().clone(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+fn check_layer_keys(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    for (layer, content) in config.world_config.layer_contents() {
+        let (kind, key, exists) = match content {
+            LayerContent::Floor(key) => (
+                "floor",
+                key,
+                config.floors_config.config_by_key(key).is_some(),
+            ),
+            LayerContent::Structure(key) => (
+                "structure",
+                key,
+                config.structures_config.config_by_key(key).is_some(),
+            ),
+            LayerContent::Tool(key) => {
+                ("tool", key, config.tool_configs.tool_by_key(key).is_some())
+            }
+            LayerContent::Item(key) => (
+                "item",
+                key,
+                config.items_config.config_by_key(key).is_some(),
+            ),
+            LayerContent::Note | LayerContent::RandomItem => continue,
+        };
+
+        if !exists {
+            errors.push(ValidationError::UnknownLayerKey {
+                layer,
+                kind,
+                key: key.clone(),
+            });
+        }
+    }
+}
+
+fn check_depth_bands(config: &GameConfiguration, errors: &mut Vec<ValidationError>) {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for band in config.world_config.bands.iter() {
+        if band.from == 0 {
+            errors.push(ValidationError::ZeroDepthBand { to: band.to });
+        }
+
+        if band.from > band.to {
+            errors.push(ValidationError::InvertedDepthBand {
+                from: band.from,
+                to: band.to,
+            });
+            continue;
+        }
+
+        let scale = config.world_config.stats_for(band.from).structure_health;
+        if scale <= 0.0 {
+            errors.push(ValidationError::InvalidStructureHealthScale {
+                from: band.from,
+                scale,
+            });
+        }
+
+        ranges.push((band.from, band.to));
+    }
+
+    ranges.sort();
+
+    for pair in ranges.windows(2) {
+        let (first, second) = (pair[0], pair[1]);
+
+        if second.0 <= first.1 {
+            errors.push(ValidationError::OverlappingDepthBands { first, second });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::KeyCode;
+    use kdl::{KdlDocument, KdlNode};
+
+    use crate::{
+        configuration::{
+            game::{GameConfiguration, ASSET_PATH},
+            key_selector::KeySelector,
+            layers::{LayerMapping, MapLayer},
+            structures::StructureConfig,
+            world::DepthBand,
+        },
+        headless::FIXTURE_CONFIG_PATH,
+    };
+
+    use super::{check_health_ranges, validate, ValidationError};
+
+    fn fixture_config() -> GameConfiguration {
+        GameConfiguration::load(FIXTURE_CONFIG_PATH).unwrap()
+    }
+
+    fn key_selector(key_code: KeyCode, display_code: &str) -> KeySelector {
+        KeySelector {
+            key_code,
+            display_code: display_code.to_string(),
+        }
+    }
+
+    fn node(kdl: &str) -> KdlNode {
+        let document: KdlDocument = kdl.parse().unwrap();
+        document.nodes()[0].clone()
+    }
+
+    #[test]
+    fn the_fixture_config_is_valid() {
+        let errors = validate(&fixture_config(), ASSET_PATH);
+
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn sprites_missing_from_the_asset_path_are_reported() {
+        let errors = validate(&fixture_config(), "tests/fixtures/missing_assets");
+
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::MissingSprite { owner, sprite }
+                if owner == "crop `kane`" && sprite == "sprites/kane_seeds.png"
+        )));
+    }
+
+    #[test]
+    fn overlapping_gapped_and_inverted_health_ranges_are_reported() {
+        let structure = StructureConfig::try_from(&node(
+            r#"structure "Cracked Wall" key="cracked_wall" type="wall" health=5 {
+                health_config sprite="sprites/brick_wall.png" min_health=1 max_health=5
+                health_config sprite="sprites/brick_wall.png" min_health=4 max_health=8
+                health_config sprite="sprites/brick_wall.png" min_health=12 max_health=15
+                health_config sprite="sprites/brick_wall.png" min_health=20 max_health=18
+            }"#,
+        ))
+        .unwrap();
+        let mut errors = Vec::new();
+
+        check_health_ranges(&structure, &mut errors);
+
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(matches!(
+            errors[0],
+            ValidationError::InvertedHealthRange {
+                min_health: 20,
+                max_health: 18,
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            ValidationError::OverlappingHealthRanges {
+                first: (1, 5),
+                second: (4, 8),
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[2],
+            ValidationError::HealthRangeGap {
+                from: 9,
+                to: 11,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn duplicate_keys_are_reported() {
+        let mut config = fixture_config();
+        let table = config
+            .structures_config
+            .config_by_key("table")
+            .unwrap()
+            .clone();
+        config.structures_config.configurations.push(table);
+
+        let errors = validate(&config, ASSET_PATH);
+
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateKey { kind: "structure", key } if key == "table"
+        )));
+    }
+
+    #[test]
+    fn key_codes_shared_on_the_inventory_screen_are_reported() {
+        let mut config = fixture_config();
+        let potato = config
+            .crops_config
+            .configurations
+            .iter_mut()
+            .find(|crop| crop.key == "potato")
+            .unwrap();
+        potato.inventory_selector = key_selector(KeyCode::K, "k");
+
+        let errors = validate(&config, ASSET_PATH);
+
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateKeyCode { key_code, first, second }
+                if key_code == "k" && first == "kane" && second == "potato"
+        )));
+    }
+
+    #[test]
+    fn an_inventory_key_code_clashes_with_controls_read_on_the_inventory_screen() {
+        let mut config = fixture_config();
+        config.crops_config.configurations[0].inventory_selector = key_selector(KeyCode::Up, "up");
+
+        let errors = validate(&config, ASSET_PATH);
+
+        // `move_north` is only read in game, `select_previous` on the inventory screen
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateKeyCode { second, .. } if second == "select_previous"
+        )));
+        assert!(!errors.iter().any(|error| matches!(
+            error,
+            ValidationError::DuplicateKeyCode { second, .. } if second == "move_north"
+        )));
+    }
+
+    #[test]
+    fn key_codes_shared_across_states_are_allowed() {
+        let config = fixture_config();
+
+        // The mushroom is selected with `m` on the inventory screen, which opens the map in game
+        assert!(config
+            .controls_config
+            .bindings
+            .iter()
+            .any(|binding| binding.action.name() == "toggle_map"));
+        assert!(!validate(&config, ASSET_PATH)
+            .iter()
+            .any(|error| matches!(error, ValidationError::DuplicateKeyCode { .. })));
+    }
+    #[test]
+    fn unknown_layer_keys_are_reported() {
+        let mut config = fixture_config();
+        config.world_config.layers.mappings.push(
+            LayerMapping::try_from(&node(r#"layer "table" { structure "golden_table"; }"#))
+                .unwrap(),
+        );
+
+        let errors = validate(&config, ASSET_PATH);
+
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::UnknownLayerKey {
+                layer: MapLayer::Table,
+                kind: "structure",
+                key,
+            } if key == "golden_table"
+        )));
+    }
+    #[test]
+    fn overlapping_depth_bands_are_reported() {
+        let mut config = fixture_config();
+        config.world_config.bands = vec![
+            DepthBand::try_from(&node("band from=1 to=5")).unwrap(),
+            DepthBand::try_from(&node("band from=3 to=8")).unwrap(),
+        ];
+
+        let errors = validate(&config, ASSET_PATH);
+
+        assert!(errors.iter().any(|error| matches!(
+            error,
+            ValidationError::OverlappingDepthBands {
+                first: (1, 5),
+                second: (3, 8),
+            }
+        )));
+    }
+}
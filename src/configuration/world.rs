@@ -1,45 +1,68 @@
 use std::num::NonZeroU16;
 
-use kdl::{KdlNode, KdlValue};
+use kdl::KdlNode;
 use tdlg::{
     generation::{builder, Generator, ItemChance, ItemGeneration},
     map::cells::LayerType,
 };
 
-use super::kdl_utils::parse;
+use super::{
+    errors::{in_file, keep_errors, ConfigError},
+    kdl_utils::{find_node, float_field, integer_field, parse, require_node, try_nodes},
+    layers::{LayerContent, LayerMapping, LayerMappings, MapLayer},
+};
 
+#[derive(PartialEq)]
 pub struct WorldGenerationConfig {
     pub world_stats: WorldStatsConfig,
+    pub layers: LayerMappings,
+    pub bands: Vec<DepthBand>,
 }
 
 impl WorldGenerationConfig {
-    pub fn load(path: &str) -> Self {
-        let nodes = parse(path).unwrap();
+    pub fn load(path: &str) -> Result<Self, Vec<ConfigError>> {
+        let nodes = parse(path).map_err(|e| vec![e])?;
 
-        let world_stats = nodes
-            .iter()
-            .find(|node| node.name().value().eq_ignore_ascii_case("world"))
-            .map_or_else(WorldStatsConfig::default, WorldStatsConfig::from);
+        let mut errors = Vec::new();
+        let world_stats = match find_node(&nodes, "world") {
+            Some(node) => keep_errors(WorldStatsConfig::try_from(node), &mut errors),
+            _ => Some(WorldStatsConfig::default()),
+        };
+        let layers = require_node(&nodes, "layers", &mut errors)
+            .and_then(|node| keep_errors(LayerMappings::try_from(node), &mut errors));
+        let bands = try_nodes(
+            nodes.iter().filter(|node| node.name().value() == "band"),
+            &mut errors,
+        );
 
-        Self { world_stats }
+        match (world_stats, layers) {
+            (Some(world_stats), Some(layers)) if errors.is_empty() => Ok(Self {
+                world_stats,
+                layers,
+                bands,
+            }),
+            _ => Err(in_file(errors, path)),
+        }
     }
 }
 
 impl WorldGenerationConfig {
-    pub fn generator(&self, seed: String) -> Generator {
+    pub fn generator(&self, seed: String, depth: usize) -> Generator {
+        let stats = self.stats_for(depth);
+
         builder()
             .seed(&seed)
-            .grid_size(NonZeroU16::new(self.world_stats.map_size).unwrap())
-            .target_number_rooms(NonZeroU16::new(self.world_stats.num_rooms).unwrap())
+            .grid_size(NonZeroU16::new(stats.map_size).unwrap())
+            .target_number_rooms(NonZeroU16::new(stats.num_rooms).unwrap())
             .target_hidden_items(ItemGeneration {
-                target_num_items: 15,
+                target_num_items: stats.hidden_items.into(),
                 item_ranges: vec![ItemChance {
                     layer_type: LayerType::Note,
                     chance: 1..100,
                 }],
             })
             .target_items(ItemGeneration {
-                target_num_items: 10,
+                target_num_items: stats.items.into(),
                 item_ranges: vec![ItemChance {
                     layer_type: LayerType::CommonItem,
                     chance: 1..100,
@@ -47,14 +70,44 @@ impl WorldGenerationConfig {
             })
             .build()
     }
+
+    pub fn band_for(&self, depth: usize) -> Option<&DepthBand> {
+        self.bands.iter().find(|band| band.contains(depth))
+    }
+
+    pub fn stats_for(&self, depth: usize) -> WorldStatsConfig {
+        match self.band_for(depth) {
+            Some(band) => band.apply(self.world_stats),
+            _ => self.world_stats,
+        }
+    }
+
+    pub fn mapping(&self, depth: usize, layer: MapLayer) -> Option<&LayerMapping> {
+        self.band_for(depth)
+            .and_then(|band| band.layers.mapping(layer))
+            .or_else(|| self.layers.mapping(layer))
+    }
+
+    pub fn layer_contents(&self) -> impl Iterator<Item = (MapLayer, &LayerContent)> {
+        self.layers
+            .contents()
+            .chain(self.bands.iter().flat_map(|band| band.layers.contents()))
+    }
 }
 
 const DEFAULT_NUM_ROOMS: u16 = 100;
 const DEFAULT_MAP_SIZE: u16 = 150;
+const DEFAULT_HIDDEN_ITEMS: u16 = 15;
+const DEFAULT_ITEMS: u16 = 10;
+const DEFAULT_STRUCTURE_HEALTH: f32 = 1.0;
 
+#[derive(Clone, Copy, PartialEq)]
 pub struct WorldStatsConfig {
     pub num_rooms: u16,
     pub map_size: u16,
+    pub hidden_items: u16,
+    pub items: u16,
+    pub structure_health: f32,
 }
 
 impl Default for WorldStatsConfig {
@@ -62,31 +115,101 @@ impl Default for WorldStatsConfig {
         Self {
             num_rooms: DEFAULT_NUM_ROOMS,
             map_size: DEFAULT_MAP_SIZE,
+            hidden_items: DEFAULT_HIDDEN_ITEMS,
+            items: DEFAULT_ITEMS,
+            structure_health: DEFAULT_STRUCTURE_HEALTH,
         }
     }
 }
 
-impl From<&KdlNode> for WorldStatsConfig {
-    fn from(node: &KdlNode) -> Self {
-        let num_rooms = match node.get("num_rooms") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as u16,
-                _ => WorldStatsConfig::default().num_rooms,
-            },
-            _ => WorldStatsConfig::default().num_rooms,
-        };
+impl TryFrom<&KdlNode> for WorldStatsConfig {
+    type Error = Vec<ConfigError>;
 
-        let map_size = match node.get("map_size") {
-            Some(entry) => match entry.value() {
-                KdlValue::Base10(it) => *it as u16,
-                _ => WorldStatsConfig::default().map_size,
-            },
-            _ => WorldStatsConfig::default().map_size,
-        };
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let stats = StatOverrides::read(node, &mut errors).apply(WorldStatsConfig::default());
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(stats)
+    }
+}
 
+#[derive(PartialEq)]
+struct StatOverrides {
+    num_rooms: Option<u16>,
+    map_size: Option<u16>,
+    hidden_items: Option<u16>,
+    items: Option<u16>,
+    structure_health: Option<f32>,
+}
+
+impl StatOverrides {
+    fn read(node: &KdlNode, errors: &mut Vec<ConfigError>) -> Self {
         Self {
-            num_rooms,
-            map_size,
+            num_rooms: integer_field(node, "num_rooms", errors).map(|it| it as u16),
+            map_size: integer_field(node, "map_size", errors).map(|it| it as u16),
+            hidden_items: integer_field(node, "hidden_items", errors).map(|it| it as u16),
+            items: integer_field(node, "items", errors).map(|it| it as u16),
+            structure_health: float_field(node, "structure_health", errors).map(|it| it as f32),
+        }
+    }
+
+    fn apply(&self, stats: WorldStatsConfig) -> WorldStatsConfig {
+        WorldStatsConfig {
+            num_rooms: self.num_rooms.unwrap_or(stats.num_rooms),
+            map_size: self.map_size.unwrap_or(stats.map_size),
+            hidden_items: self.hidden_items.unwrap_or(stats.hidden_items),
+            items: self.items.unwrap_or(stats.items),
+            structure_health: self.structure_health.unwrap_or(stats.structure_health),
+        }
+    }
+}
+
+// Depth 1 is the first floor, so `band from=1 to=3` covers the first three floors
+#[derive(PartialEq)]
+pub struct DepthBand {
+    pub from: usize,
+    pub to: usize,
+    overrides: StatOverrides,
+    pub layers: LayerMappings,
+}
+
+impl DepthBand {
+    pub fn contains(&self, depth: usize) -> bool {
+        (self.from..=self.to).contains(&depth)
+    }
+
+    fn apply(&self, stats: WorldStatsConfig) -> WorldStatsConfig {
+        self.overrides.apply(stats)
+    }
+}
+
+impl TryFrom<&KdlNode> for DepthBand {
+    type Error = Vec<ConfigError>;
+
+    fn try_from(node: &KdlNode) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+
+        let from = integer_field(node, "from", &mut errors);
+        let to = integer_field(node, "to", &mut errors);
+        if node.get("from").is_none() {
+            errors.push(ConfigError::missing_field(node, "from"));
+        }
+        let overrides = StatOverrides::read(node, &mut errors);
+        let layers = keep_errors(LayerMappings::try_from(node), &mut errors);
+
+        match (from, layers) {
+            (Some(from), Some(layers)) if errors.is_empty() => Ok(Self {
+                from: from as usize,
+                to: to.map_or(usize::MAX, |it| it as usize),
+                overrides,
+                layers,
+            }),
+            _ => Err(errors),
         }
     }
 }
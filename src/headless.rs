@@ -0,0 +1,428 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{
+    input::{Axis, Input},
+    math::{Vec2, Vec3},
+    prelude::{
+        App, Entity, Gamepad, GamepadAxis, GamepadButton, GamepadButtonType, Gamepads, KeyCode,
+        MinimalPlugins, Mut, PluginGroup, Resource, State, Transform, With,
+    },
+    time::{Time, TimePlugin},
+    utils::Instant,
+};
+
+use crate::{
+    components::{
+        crop::{Crop, CropBundle, CropSpawn},
+        floor::FloorLevel,
+        ground::GroundTile,
+        moisture::SoilMoisture,
+        player::{Player, PlayerInventory},
+        structure::{Structure, StructureBundle},
+        world::World as GameWorld,
+    },
+    configuration::{crops::SpreadConfig, game::GameConfiguration},
+    plugins::GameplayPlugin,
+    rng::{GameRng, RngStream},
+    save::LoadedSave,
+    sprites::Sprites,
+    states::{AppState, GameLoadState},
+    systems::textures::assign_sprite_indices,
+};
+
+mod scenarios;
+
+pub const FIXTURE_CONFIG_PATH: &str = "tests/fixtures/config";
+
+const FRAME_TIME: Duration = Duration::from_millis(16);
+const MAX_STARTUP_FRAMES: usize = 10;
+
+// Stands in for the texture atlas, every distinct sprite path gets its own index
+#[derive(Default, Resource)]
+pub struct StubAtlas {
+    indices: HashMap<String, usize>,
+}
+
+impl StubAtlas {
+    pub fn build(game_config: &mut GameConfiguration) -> Self {
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        assign_sprite_indices(game_config, |sprite_location| {
+            let next_index = indices.len();
+            Some(
+                *indices
+                    .entry(sprite_location.to_string())
+                    .or_insert(next_index),
+            )
+        });
+
+        Self { indices }
+    }
+
+    pub fn index(&self, sprite_location: &str) -> usize {
+        self.indices[sprite_location]
+    }
+}
+
+// Runs the gameplay systems without a window, textures or a save file. Time is stepped by hand
+// so timers behave the same on every run
+pub fn headless_app(config_path: &str) -> App {
+    let mut game_config = match GameConfiguration::load(config_path) {
+        Ok(it) => it,
+        Err(errors) => {
+            let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+            panic!("could not load {config_path}\n{}", messages.join("\n"));
+        }
+    };
+    let atlas = StubAtlas::build(&mut game_config);
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+        .init_resource::<Time>()
+        .init_resource::<Input<KeyCode>>()
+        .init_resource::<Gamepads>()
+        .init_resource::<Input<GamepadButton>>()
+        .init_resource::<Axis<GamepadAxis>>()
+        .init_resource::<Sprites>()
+        .insert_resource(GameLoadState {
+            texture_load_complete: true,
+            textures_set: true,
+        })
+        .insert_resource(game_config)
+        .insert_resource(LoadedSave(None))
+        .insert_resource(atlas)
+        .add_state(AppState::FinishedLoading)
+        .add_plugin(GameplayPlugin);
+
+    app
+}
+
+// Drives a headless app frame by frame with scripted key presses
+pub struct Simulation {
+    pub app: App,
+    now: Instant,
+}
+
+impl Simulation {
+    pub fn new(config_path: &str) -> Self {
+        let mut simulation = Self {
+            app: headless_app(config_path),
+            now: Instant::now(),
+        };
+
+        for _ in 0..MAX_STARTUP_FRAMES {
+            simulation.step();
+            if simulation.player_entity().is_some() {
+                return simulation;
+            }
+        }
+
+        panic!("player was not spawned after {MAX_STARTUP_FRAMES} frames");
+    }
+
+    pub fn step(&mut self) {
+        self.now += FRAME_TIME;
+        let now = self.now;
+        self.app
+            .world
+            .resource_mut::<Time>()
+            .update_with_instant(now);
+
+        self.app.update();
+
+        self.app.world.resource_mut::<Input<KeyCode>>().clear();
+        self.app
+            .world
+            .resource_mut::<Input<GamepadButton>>()
+            .clear();
+    }
+
+    pub fn press(&mut self, key_code: KeyCode) {
+        self.app
+            .world
+            .resource_mut::<Input<KeyCode>>()
+            .press(key_code);
+    }
+
+    pub fn release(&mut self, key_code: KeyCode) {
+        self.app
+            .world
+            .resource_mut::<Input<KeyCode>>()
+            .release(key_code);
+    }
+
+    pub fn tap(&mut self, key_code: KeyCode) {
+        self.press(key_code);
+        self.step();
+        self.release(key_code);
+        self.step();
+    }
+
+    pub fn press_button(&mut self, button_type: GamepadButtonType) {
+        self.app
+            .world
+            .resource_mut::<Input<GamepadButton>>()
+            .press(GamepadButton::new(Gamepad::new(0), button_type));
+    }
+
+    pub fn release_button(&mut self, button_type: GamepadButtonType) {
+        self.app
+            .world
+            .resource_mut::<Input<GamepadButton>>()
+            .release(GamepadButton::new(Gamepad::new(0), button_type));
+    }
+
+    pub fn tap_button(&mut self, button_type: GamepadButtonType) {
+        self.press_button(button_type);
+        self.step();
+        self.release_button(button_type);
+        self.step();
+    }
+
+    pub fn current_state(&self) -> AppState {
+        self.app
+            .world
+            .resource::<State<AppState>>()
+            .current()
+            .clone()
+    }
+
+    pub fn level(&self) -> usize {
+        self.app.world.resource::<GameConfiguration>().level()
+    }
+
+    pub fn sprite_index(&self, sprite_location: &str) -> usize {
+        self.app
+            .world
+            .resource::<StubAtlas>()
+            .index(sprite_location)
+    }
+
+    pub fn player_entity(&mut self) -> Option<Entity> {
+        self.app
+            .world
+            .query_filtered::<Entity, With<Player>>()
+            .iter(&self.app.world)
+            .next()
+    }
+
+    pub fn player_position(&mut self) -> Vec3 {
+        let entity = self.player_entity().unwrap();
+        self.app.world.get::<Transform>(entity).unwrap().translation
+    }
+
+    pub fn inventory(&mut self) -> Mut<'_, PlayerInventory> {
+        let entity = self.player_entity().unwrap();
+        self.app.world.get_mut::<PlayerInventory>(entity).unwrap()
+    }
+
+    pub fn give_tool(&mut self, key: &str) {
+        let tool = self
+            .app
+            .world
+            .resource::<GameConfiguration>()
+            .tool_configs
+            .tool_by_key(key)
+            .unwrap_or_else(|| panic!("no tool `{key}` in the fixture config"));
+
+        self.inventory().held_tools.push(tool);
+    }
+
+    // Map structures make it hard to script movement, so scenarios usually start from a clean floor
+    pub fn clear_structures(&mut self) {
+        let entities: Vec<Entity> = self
+            .app
+            .world
+            .query_filtered::<Entity, With<Structure>>()
+            .iter(&self.app.world)
+            .collect();
+
+        for entity in entities {
+            self.app.world.despawn(entity);
+        }
+    }
+
+    // The floor under the player is picked at random, scenarios that dig or plant pin it down
+    pub fn set_ground_tillable(&mut self, tillable: bool) {
+        let player_position = self.player_position();
+        let mut query = self.app.world.query::<(&mut GroundTile, &Transform)>();
+        for (mut ground, transform) in query.iter_mut(&mut self.app.world) {
+            if transform.translation.truncate() == player_position.truncate() {
+                ground.tillable = tillable;
+            }
+        }
+    }
+
+    pub fn spawn_structure(&mut self, key: &str, tiles_from_player: Vec2) -> Entity {
+        let player_position = self.player_position();
+        let atlas_handle = self.app.world.resource::<Sprites>().atlas_handle.clone();
+
+        let game_config = self.app.world.resource::<GameConfiguration>();
+        let tile_size = game_config.tile_size();
+        let structure_config = game_config
+            .structures_config
+            .config_by_key(key)
+            .unwrap_or_else(|| panic!("no structure `{key}` in the fixture config"));
+        let position = Vec3::new(
+            player_position.x + tiles_from_player.x * tile_size,
+            player_position.y + tiles_from_player.y * tile_size,
+            1.0,
+        );
+
+        let bundle = StructureBundle::build(
+            position,
+            &atlas_handle,
+            structure_config,
+            &game_config.sprite_config,
+        );
+
+        let level = FloorLevel(game_config.level());
+        let entity = self.app.world.spawn((bundle, level)).id();
+
+        // The tile occupancy index only picks new entities up at the end of a frame
+        self.step();
+        entity
+    }
+
+    pub fn set_soil_moisture(&mut self, entity: Entity, percent: u32) {
+        let mut moisture = SoilMoisture::default();
+        moisture.water(percent);
+        self.app.world.entity_mut(entity).insert(moisture);
+    }
+
+    pub fn soil_moisture(&mut self) -> Option<u32> {
+        self.app
+            .world
+            .query::<&SoilMoisture>()
+            .iter(&self.app.world)
+            .next()
+            .map(SoilMoisture::percent)
+    }
+
+    pub fn spawn_crop(&mut self, key: &str, tiles_from_player: Vec2) -> Entity {
+        let player_position = self.player_position();
+
+        let bundle = self
+            .app
+            .world
+            .resource_scope(|world, mut game_rng: Mut<GameRng>| {
+                let game_config = world.resource::<GameConfiguration>();
+                let crop_config = game_config
+                    .crops_config
+                    .config_by_key(key)
+                    .unwrap_or_else(|| panic!("no crop `{key}` in the fixture config"));
+                let spawn = CropSpawn {
+                    location: player_position.truncate()
+                        + tiles_from_player * game_config.tile_size(),
+                    config: crop_config.clone(),
+                };
+
+                CropBundle::build(
+                    &spawn,
+                    world.resource::<Sprites>(),
+                    crop_config,
+                    game_config.sprite_config.crop_scale,
+                    game_config.sprite_config.scale,
+                    game_rng.stream(game_config, RngStream::Crops),
+                )
+            });
+
+        let level = FloorLevel(self.level());
+        let entity = self.app.world.spawn((bundle, level)).id();
+
+        self.step();
+        entity
+    }
+
+    pub fn mature_crop(&mut self, entity: Entity) {
+        let mut crop = self.app.world.get_mut::<Crop>(entity).unwrap();
+        crop.current_stage_index = crop.config.stages.len() - 1;
+    }
+
+    pub fn mature_crop_with_spread(&mut self, entity: Entity, spread: SpreadConfig) {
+        self.mature_crop(entity);
+        let mut crop = self.app.world.get_mut::<Crop>(entity).unwrap();
+        crop.config.spread = Some(spread);
+    }
+
+    pub fn set_breeding_chance(&mut self, hybrid_key: &str, chance: u32) {
+        let mut game_config = self.app.world.resource_mut::<GameConfiguration>();
+        let recipe = game_config
+            .crops_config
+            .configurations
+            .iter_mut()
+            .find(|crop_config| crop_config.key == hybrid_key)
+            .and_then(|crop_config| crop_config.breeding.as_mut())
+            .unwrap_or_else(|| panic!("no hybrid `{hybrid_key}` in the fixture config"));
+        recipe.chance = chance;
+    }
+
+    pub fn held_seeds(&mut self, key: &str) -> u32 {
+        self.inventory()
+            .held_seeds
+            .iter()
+            .find(|seeds| seeds.config.key == key)
+            .map_or(0, |seeds| seeds.quantity)
+    }
+
+    pub fn count_crops(&mut self) -> usize {
+        self.app
+            .world
+            .query::<&Crop>()
+            .iter(&self.app.world)
+            .count()
+    }
+
+    pub fn crop_is_mature(&mut self, key: &str) -> Option<bool> {
+        self.app
+            .world
+            .query::<&Crop>()
+            .iter(&self.app.world)
+            .find(|crop| crop.config.key == key)
+            .map(Crop::is_mature)
+    }
+
+    pub fn crop_is_withered(&mut self, key: &str) -> Option<bool> {
+        self.app
+            .world
+            .query::<&Crop>()
+            .iter(&self.app.world)
+            .find(|crop| crop.config.key == key)
+            .map(|crop| crop.withered)
+    }
+
+    pub fn world_ticks(&mut self) -> u64 {
+        self.app
+            .world
+            .query::<&GameWorld>()
+            .iter(&self.app.world)
+            .next()
+            .map_or(0, |world| world.ticks)
+    }
+
+    pub fn run_world_ticks(&mut self, ticks: u64) {
+        let target = self.world_ticks() + ticks;
+        while self.world_ticks() < target {
+            self.step();
+        }
+    }
+
+    pub fn advance_world_ticks(&mut self, ticks: u64) {
+        let mut query = self.app.world.query::<&mut GameWorld>();
+        for mut world in query.iter_mut(&mut self.app.world) {
+            world.ticks += ticks;
+        }
+    }
+
+    pub fn structure(&self, entity: Entity) -> &Structure {
+        self.app.world.get::<Structure>(entity).unwrap()
+    }
+
+    pub fn count_structures(&mut self, key: &str) -> usize {
+        self.app
+            .world
+            .query::<&Structure>()
+            .iter(&self.app.world)
+            .filter(|structure| structure.key() == key)
+            .count()
+    }
+}
@@ -0,0 +1,238 @@
+use bevy::{
+    math::Vec2,
+    prelude::{GamepadButtonType, KeyCode},
+};
+
+use crate::{configuration::crops::SpreadConfig, states::AppState};
+
+use super::{Simulation, FIXTURE_CONFIG_PATH};
+
+const MAX_WALL_HITS: usize = 100;
+const TICKS_AWAY: u64 = 1000;
+// Long enough for dry seeds to wither, too short for them to die off
+const TICKS_TO_WITHER: u64 = 450;
+
+fn equip(simulation: &mut Simulation, key_code: KeyCode) {
+    simulation.tap(KeyCode::I);
+    assert_eq!(simulation.current_state(), AppState::InventoryScreen);
+
+    simulation.tap(key_code);
+
+    simulation.tap(KeyCode::I);
+    assert_eq!(simulation.current_state(), AppState::InGame);
+}
+
+#[test]
+fn digging_with_the_shovel_leaves_a_dug_spot() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    simulation.set_ground_tillable(false);
+    simulation.give_tool("bone_shovel");
+
+    equip(&mut simulation, KeyCode::S);
+    assert!(simulation.inventory().shovel_equipped());
+
+    simulation.tap(KeyCode::E);
+
+    assert_eq!(simulation.count_structures("dug_spot"), 1);
+    assert_eq!(simulation.count_structures("tilled_soil"), 0);
+}
+
+#[test]
+fn seeds_only_go_into_tilled_soil() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    simulation.set_ground_tillable(true);
+    simulation.give_tool("bone_shovel");
+
+    equip(&mut simulation, KeyCode::K);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.count_crops(), 0);
+
+    equip(&mut simulation, KeyCode::S);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.count_structures("tilled_soil"), 1);
+
+    equip(&mut simulation, KeyCode::K);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.count_crops(), 1);
+}
+
+#[test]
+fn walking_into_a_room_wall_with_the_pickaxe_breaks_it() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    let wall = simulation.spawn_structure("room_wall", Vec2::new(1.0, 0.0));
+
+    equip(&mut simulation, KeyCode::A);
+
+    let broken_index = simulation.sprite_index("sprites/broken_wall.png");
+    let mut hits = 0;
+    while simulation.structure(wall).current_texture_index() != Some(broken_index) {
+        assert!(hits < MAX_WALL_HITS, "wall never reached the broken band");
+
+        simulation.tap(KeyCode::Right);
+        hits += 1;
+    }
+
+    assert!(simulation.structure(wall).can_be_walked_on());
+    assert!(simulation.structure(wall).health.current_health <= 0);
+}
+
+#[test]
+fn gamepad_and_keyboard_both_move_the_player() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    let start = simulation.player_position();
+
+    simulation.tap_button(GamepadButtonType::DPadRight);
+    let after_gamepad = simulation.player_position();
+    assert!(after_gamepad.x > start.x);
+
+    simulation.tap(KeyCode::Right);
+    assert!(simulation.player_position().x > after_gamepad.x);
+}
+
+#[test]
+fn climbing_back_up_returns_to_the_floor_as_it_was_left() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    simulation.spawn_structure("dug_spot", Vec2::new(1.0, 0.0));
+    simulation.spawn_structure("exit", Vec2::ZERO);
+    let start = simulation.player_position();
+
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 1);
+    assert_eq!(simulation.count_structures("dug_spot"), 0);
+    assert_eq!(simulation.count_structures("ladder"), 1);
+
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 0);
+    assert_eq!(simulation.count_structures("dug_spot"), 1);
+    assert_eq!(simulation.count_structures("ladder"), 0);
+    assert_eq!(simulation.player_position(), start);
+}
+
+#[test]
+fn crops_keep_growing_while_their_floor_is_unloaded() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    simulation.spawn_structure("exit", Vec2::ZERO);
+    let soil = simulation.spawn_structure("tilled_soil", Vec2::new(1.0, 0.0));
+    simulation.set_soil_moisture(soil, 100);
+    simulation.spawn_crop("kane", Vec2::new(1.0, 0.0));
+    assert_eq!(simulation.crop_is_mature("kane"), Some(false));
+
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 1);
+    assert_eq!(simulation.crop_is_mature("kane"), None);
+
+    simulation.advance_world_ticks(TICKS_AWAY);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 0);
+    assert_eq!(simulation.crop_is_mature("kane"), Some(true));
+}
+
+#[test]
+fn watered_crops_grow_while_dry_ones_wither() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    simulation.set_ground_tillable(true);
+    simulation.give_tool("bone_shovel");
+    simulation.give_tool("watering_can");
+
+    equip(&mut simulation, KeyCode::S);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.soil_moisture(), Some(0));
+
+    equip(&mut simulation, KeyCode::W);
+    simulation.tap(KeyCode::E);
+    assert!(simulation.soil_moisture().unwrap() > 50);
+
+    // Topped up so the kane outlasts even the fastest draining floor
+    simulation.tap(KeyCode::E);
+    assert!(simulation.soil_moisture().unwrap() > 90);
+
+    equip(&mut simulation, KeyCode::K);
+    simulation.tap(KeyCode::E);
+
+    simulation.spawn_structure("tilled_soil", Vec2::new(1.0, 0.0));
+    simulation.spawn_crop("potato", Vec2::new(1.0, 0.0));
+    simulation.spawn_structure("exit", Vec2::ZERO);
+
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 1);
+
+    simulation.advance_world_ticks(TICKS_TO_WITHER);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 0);
+    assert_eq!(simulation.crop_is_mature("kane"), Some(true));
+    assert_eq!(simulation.crop_is_withered("kane"), Some(false));
+    assert_eq!(simulation.crop_is_withered("potato"), Some(true));
+}
+
+#[test]
+fn dead_crops_leave_a_plant_that_the_hoe_clears() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    simulation.spawn_structure("exit", Vec2::ZERO);
+    simulation.spawn_structure("tilled_soil", Vec2::new(1.0, 0.0));
+    simulation.spawn_crop("potato", Vec2::new(1.0, 0.0));
+
+    simulation.tap(KeyCode::E);
+    simulation.advance_world_ticks(TICKS_AWAY);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.level(), 0);
+    assert_eq!(simulation.crop_is_withered("potato"), None);
+    assert_eq!(simulation.count_structures("dead_plant"), 1);
+
+    simulation.tap(KeyCode::Right);
+    simulation.give_tool("stone_hoe");
+    equip(&mut simulation, KeyCode::H);
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.count_structures("dead_plant"), 0);
+    assert_eq!(simulation.count_structures("tilled_soil"), 1);
+}
+
+#[test]
+fn mature_crops_spread_onto_tilled_soil_until_the_floor_is_full() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    for tile in [
+        Vec2::new(1.0, 0.0),
+        Vec2::new(2.0, 0.0),
+        Vec2::new(1.0, 1.0),
+    ] {
+        let soil = simulation.spawn_structure("tilled_soil", tile);
+        simulation.set_soil_moisture(soil, 100);
+    }
+    let mushroom = simulation.spawn_crop("mushroom", Vec2::new(1.0, 0.0));
+    simulation.mature_crop_with_spread(
+        mushroom,
+        SpreadConfig {
+            chance: 10_000,
+            max_density: 2,
+        },
+    );
+
+    simulation.run_world_ticks(5);
+    assert_eq!(simulation.count_crops(), 2);
+}
+
+#[test]
+fn harvesting_next_to_a_breeding_partner_yields_hybrid_seeds() {
+    let mut simulation = Simulation::new(FIXTURE_CONFIG_PATH);
+    simulation.clear_structures();
+    let kane = simulation.spawn_crop("kane", Vec2::ZERO);
+    let turnip = simulation.spawn_crop("turnip", Vec2::new(1.0, 0.0));
+    simulation.mature_crop(kane);
+    simulation.mature_crop(turnip);
+    simulation.set_breeding_chance("sweet_kane", 100);
+    simulation.step();
+    let kane_seeds = simulation.held_seeds("kane");
+
+    simulation.tap(KeyCode::E);
+    assert_eq!(simulation.count_crops(), 1);
+    assert_eq!(simulation.held_seeds("kane"), kane_seeds + 1);
+    assert_eq!(simulation.held_seeds("sweet_kane"), 1);
+}
@@ -0,0 +1,341 @@
+use bevy::{
+    input::{Input, InputSystem},
+    prelude::{App, CoreStage, IntoSystemDescriptor, Plugin, SystemLabel, SystemSet},
+};
+
+use crate::{
+    configuration::controls::InputAction,
+    rng::GameRng,
+    save::VisitedFloors,
+    states::AppState,
+    systems::{
+        self,
+        actions::{
+            clear_structure_action, crop_actions, dig_action, harvest_action, hit_actions,
+            pickup_actions, reset_hit_actions, reset_pickup_actions, water_action,
+        },
+        cameras::add_gameplay_camera,
+        config_reload::{reload_config_system, ConfigWatcher},
+        controls::update_input_actions,
+        crops::{
+            add_soil_moisture, clear_dead_crops, drain_soil_moisture, grow_crops_system,
+            spread_crops_system,
+        },
+        floors::change_floor,
+        fov::{apply_field_of_view, update_field_of_view, FieldOfView},
+        initial_spawns::{spawn_opening_bundles, spawn_player_text},
+        inputs::{
+            action_input_system, movement_input_system, reset_action_input_system,
+            toggle_coordinates_system, zoom_camera_system, MovementInputTimer,
+        },
+        inventory::{
+            add_current_selection, add_text, hide_game_sprites, open_close_inventory_input_system,
+            remove_gameplay_camera, remove_text, remove_ui_camera, reset_selection, select_item,
+            selection_input, show_game_sprites, update_text_colour,
+        },
+        loading::start_game,
+        map_view::{
+            hide_map_views, show_map_views, spawn_map_views, toggle_map_system, update_map_image,
+            FloorLayout, MapView,
+        },
+        movement::{
+            camera_movement, check_item_pickup, player_movement, update_player_grid_coordinate,
+            update_player_text,
+        },
+        notes::{
+            add_note_reader, add_note_text, note_page_input, open_close_note_input_system,
+            remove_note_text, update_note_text,
+        },
+        occupancy::{update_tile_occupancy, TileOccupancy},
+        save::save_game_system,
+        spawns::{
+            reset_crop_spawns, reset_spawn_map, reset_structure_spawns, spawn_crops, spawn_map,
+            spawn_structures,
+        },
+        world::{check_world_actions, tick_game_world},
+    },
+};
+
+// System labels to enforce a run order of our systems
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+enum Label {
+    OpeningSpawn,
+    MovementInput,
+    PlayerMovement,
+    ActionInput,
+    DigAction,
+    ChangeFloor,
+    ClearStructureAction,
+    CameraMovement,
+    UpdatePlayerGridCoordinate,
+    UpdateFieldOfView,
+    CropActions,
+    HarvestAction,
+    WaterAction,
+    CheckItemPickup,
+    HitActions,
+    PickupActions,
+    GrowCrops,
+    SpreadCrops,
+    ClearDeadCrops,
+    TickGameWorld,
+    SpawnCrops,
+    SpawnStructures,
+    SpawnMap,
+    ResetSpawnMap,
+    ResetInventorySelection,
+    SelectItem,
+    InventoryInput,
+    NotePageInput,
+    CheckWorldActions,
+    ToggleMap,
+}
+
+// Everything that changes the game world, it has to run without a window or loaded textures
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MovementInputTimer>()
+            .init_resource::<GameRng>()
+            .init_resource::<FieldOfView>()
+            .init_resource::<FloorLayout>()
+            .init_resource::<TileOccupancy>()
+            .init_resource::<VisitedFloors>()
+            .init_resource::<Input<InputAction>>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_input_actions.after(InputSystem),
+            )
+            .add_system_to_stage(CoreStage::PostUpdate, update_tile_occupancy)
+            .add_system_to_stage(CoreStage::PostUpdate, add_soil_moisture)
+            .add_system_set(SystemSet::on_update(AppState::FinishedLoading).with_system(start_game))
+            .add_system_set(
+                SystemSet::on_exit(AppState::FinishedLoading).with_system(systems::world::spawn),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::InGame)
+                    .with_system(spawn_opening_bundles.label(Label::OpeningSpawn))
+                    .with_system(add_current_selection)
+                    .with_system(add_note_reader),
+            )
+            .add_system(open_close_inventory_input_system)
+            .add_system(open_close_note_input_system)
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(tick_game_world.label(Label::TickGameWorld))
+                    .with_system(
+                        check_world_actions
+                            .label(Label::CheckWorldActions)
+                            .after(Label::TickGameWorld),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::ReadingNote)
+                    .with_system(note_page_input.label(Label::NotePageInput)),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InventoryScreen)
+                    .with_system(selection_input.label(Label::InventoryInput))
+                    .with_system(
+                        select_item
+                            .after(Label::InventoryInput)
+                            .label(Label::SelectItem),
+                    )
+                    .with_system(
+                        reset_selection
+                            .label(Label::ResetInventorySelection)
+                            .after(Label::SelectItem),
+                    ),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(movement_input_system.label(Label::MovementInput))
+                    .with_system(
+                        player_movement
+                            .label(Label::PlayerMovement)
+                            .after(Label::MovementInput),
+                    )
+                    .with_system(
+                        action_input_system
+                            .label(Label::ActionInput)
+                            .after(Label::PlayerMovement),
+                    )
+                    .with_system(dig_action.label(Label::DigAction).after(Label::ActionInput))
+                    .with_system(
+                        reset_action_input_system
+                            .after(Label::CropActions)
+                            .after(Label::HarvestAction)
+                            .after(Label::WaterAction)
+                            .after(Label::DigAction)
+                            .after(Label::ClearStructureAction)
+                            .after(Label::ChangeFloor),
+                    )
+                    .with_system(
+                        check_item_pickup
+                            .label(Label::CheckItemPickup)
+                            .after(Label::PlayerMovement),
+                    )
+                    .with_system(
+                        update_player_grid_coordinate
+                            .label(Label::UpdatePlayerGridCoordinate)
+                            .after(Label::PlayerMovement),
+                    )
+                    .with_system(
+                        update_field_of_view
+                            .label(Label::UpdateFieldOfView)
+                            .after(Label::UpdatePlayerGridCoordinate),
+                    )
+                    .with_system(apply_field_of_view.after(Label::UpdateFieldOfView))
+                    .with_system(
+                        hit_actions
+                            .label(Label::HitActions)
+                            .after(Label::PlayerMovement),
+                    )
+                    .with_system(
+                        pickup_actions
+                            .label(Label::PickupActions)
+                            .after(Label::CheckItemPickup),
+                    )
+                    .with_system(
+                        clear_structure_action
+                            .label(Label::ClearStructureAction)
+                            .after(Label::ActionInput),
+                    )
+                    .with_system(reset_hit_actions.after(Label::HitActions))
+                    .with_system(reset_pickup_actions.after(Label::PickupActions))
+                    .with_system(
+                        crop_actions
+                            .label(Label::CropActions)
+                            .after(Label::ActionInput),
+                    )
+                    .with_system(
+                        harvest_action
+                            .label(Label::HarvestAction)
+                            .after(Label::ActionInput),
+                    )
+                    .with_system(
+                        water_action
+                            .label(Label::WaterAction)
+                            .after(Label::ActionInput),
+                    )
+                    .with_system(
+                        drain_soil_moisture
+                            .after(Label::TickGameWorld)
+                            .after(Label::WaterAction)
+                            .before(Label::GrowCrops),
+                    )
+                    .with_system(
+                        grow_crops_system
+                            .label(Label::GrowCrops)
+                            .after(Label::TickGameWorld),
+                    )
+                    .with_system(
+                        spread_crops_system
+                            .label(Label::SpreadCrops)
+                            .after(Label::CropActions)
+                            .after(Label::GrowCrops),
+                    )
+                    .with_system(
+                        clear_dead_crops
+                            .label(Label::ClearDeadCrops)
+                            .after(Label::GrowCrops),
+                    )
+                    .with_system(
+                        spawn_crops
+                            .label(Label::SpawnCrops)
+                            .after(Label::CropActions)
+                            .after(Label::SpreadCrops),
+                    )
+                    .with_system(
+                        spawn_structures
+                            .label(Label::SpawnStructures)
+                            .after(Label::DigAction)
+                            .after(Label::ClearDeadCrops),
+                    )
+                    .with_system(reset_structure_spawns.after(Label::SpawnStructures))
+                    .with_system(
+                        change_floor
+                            .after(Label::ActionInput)
+                            .label(Label::ChangeFloor),
+                    )
+                    .with_system(reset_crop_spawns.after(Label::SpawnCrops))
+                    .with_system(spawn_map.label(Label::SpawnMap).after(Label::ChangeFloor))
+                    .with_system(
+                        reset_spawn_map
+                            .label(Label::ResetSpawnMap)
+                            .after(Label::SpawnMap),
+                    ),
+            );
+    }
+}
+
+// Cameras, text, saving and config reloading, these need the window and asset server
+pub struct PresentationPlugin;
+
+impl Plugin for PresentationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConfigWatcher>()
+            .init_resource::<MapView>()
+            .add_system_set(
+                SystemSet::on_enter(AppState::InGame)
+                    .with_system(spawn_player_text)
+                    .with_system(spawn_map_views)
+                    .with_system(add_gameplay_camera),
+            )
+            .add_system(save_game_system)
+            .add_system(reload_config_system)
+            .add_system_set(
+                SystemSet::on_enter(AppState::InventoryScreen)
+                    .with_system(add_text)
+                    .with_system(remove_gameplay_camera)
+                    .with_system(hide_game_sprites)
+                    .with_system(hide_map_views),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::InventoryScreen)
+                    .with_system(remove_text)
+                    .with_system(remove_ui_camera)
+                    .with_system(show_game_sprites),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::ReadingNote)
+                    .with_system(add_note_text)
+                    .with_system(remove_gameplay_camera)
+                    .with_system(hide_game_sprites)
+                    .with_system(hide_map_views),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::ReadingNote)
+                    .with_system(remove_note_text)
+                    .with_system(remove_ui_camera)
+                    .with_system(show_game_sprites),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::ReadingNote)
+                    .with_system(update_note_text.after(Label::NotePageInput)),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InventoryScreen)
+                    .with_system(update_text_colour.after(Label::InventoryInput)),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(update_player_text.after(Label::UpdatePlayerGridCoordinate))
+                    .with_system(
+                        camera_movement
+                            .label(Label::CameraMovement)
+                            .after(Label::PlayerMovement),
+                    )
+                    .with_system(zoom_camera_system)
+                    .with_system(toggle_coordinates_system)
+                    .with_system(toggle_map_system.label(Label::ToggleMap))
+                    .with_system(show_map_views.after(Label::ToggleMap))
+                    .with_system(
+                        update_map_image
+                            .after(Label::UpdateFieldOfView)
+                            .after(Label::SpawnMap),
+                    ),
+            );
+    }
+}
@@ -0,0 +1,143 @@
+use std::{collections::HashMap, hash::Hasher, str::FromStr};
+
+use bevy::prelude::Resource;
+use fnv::FnvHasher;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{configuration::game::GameConfiguration, save::RngSave};
+
+// Each subsystem draws from its own stream so extra rolls in one can't shift another
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    Layout,
+    FloorSprites,
+    Items,
+    Notes,
+    Crops,
+    Combat,
+}
+
+impl RngStream {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Layout => "layout",
+            Self::FloorSprites => "floor_sprites",
+            Self::Items => "items",
+            Self::Notes => "notes",
+            Self::Crops => "crops",
+            Self::Combat => "combat",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseRngStreamError;
+
+impl FromStr for RngStream {
+    type Err = ParseRngStreamError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "layout" => Ok(Self::Layout),
+            "floor_sprites" => Ok(Self::FloorSprites),
+            "items" => Ok(Self::Items),
+            "notes" => Ok(Self::Notes),
+            "crops" => Ok(Self::Crops),
+            "combat" => Ok(Self::Combat),
+            _ => Err(ParseRngStreamError),
+        }
+    }
+}
+
+// FNV and ChaCha are both fixed algorithms, so a seed rolls the same on any build of the game,
+// which std's hasher and `StdRng` don't promise
+pub fn floor_stream(floor_seed: &str, stream: RngStream) -> ChaCha8Rng {
+    let mut hasher = FnvHasher::default();
+    hasher.write(floor_seed.as_bytes());
+    hasher.write_u8(0xff);
+    hasher.write(stream.key().as_bytes());
+
+    ChaCha8Rng::seed_from_u64(hasher.finish())
+}
+
+// Every floor keeps its own streams, so leaving a floor and coming back, or saving and loading,
+// carries on its rolls where they left off instead of replaying them
+#[derive(Default, Resource)]
+pub struct GameRng {
+    streams: HashMap<(String, RngStream), ChaCha8Rng>,
+}
+
+impl GameRng {
+    pub fn stream(
+        &mut self,
+        game_config: &GameConfiguration,
+        stream: RngStream,
+    ) -> &mut ChaCha8Rng {
+        self.streams
+            .entry((game_config.floor_seed(), stream))
+            .or_insert_with_key(|(floor_seed, stream)| floor_stream(floor_seed, *stream))
+    }
+
+    pub fn positions(&self) -> Vec<RngSave> {
+        let mut positions: Vec<RngSave> = self
+            .streams
+            .iter()
+            .map(|((floor_seed, stream), rng)| RngSave {
+                floor_seed: floor_seed.clone(),
+                stream: *stream,
+                position: rng.get_word_pos(),
+            })
+            .collect();
+        positions
+            .sort_by(|a, b| (&a.floor_seed, a.stream.key()).cmp(&(&b.floor_seed, b.stream.key())));
+        positions
+    }
+
+    pub fn restore(&mut self, positions: &[RngSave]) {
+        self.streams.clear();
+        for saved in positions {
+            let mut rng = floor_stream(&saved.floor_seed, saved.stream);
+            rng.set_word_pos(saved.position);
+            self.streams
+                .insert((saved.floor_seed.clone(), saved.stream), rng);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use crate::{configuration::game::GameConfiguration, headless::FIXTURE_CONFIG_PATH};
+
+    use super::{floor_stream, GameRng, RngStream};
+
+    #[test]
+    fn a_floor_seed_rolls_the_same_numbers_on_every_build() {
+        let mut rng = floor_stream("cave-3", RngStream::Items);
+
+        assert_eq!(rng.next_u64(), 699130705472466486);
+        assert_eq!(rng.next_u64(), 9145038203733675262);
+    }
+
+    #[test]
+    fn restored_positions_carry_on_with_the_next_roll() {
+        let config = GameConfiguration::load(FIXTURE_CONFIG_PATH).unwrap();
+        let mut game_rng = GameRng::default();
+        for _ in 0..5 {
+            game_rng.stream(&config, RngStream::Crops).next_u64();
+        }
+        game_rng.stream(&config, RngStream::Combat).next_u32();
+
+        let mut restored = GameRng::default();
+        restored.restore(&game_rng.positions());
+
+        for stream in [RngStream::Crops, RngStream::Combat, RngStream::Items] {
+            assert_eq!(
+                restored.stream(&config, stream).next_u64(),
+                game_rng.stream(&config, stream).next_u64()
+            );
+        }
+    }
+}
@@ -0,0 +1,837 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use bevy::{
+    math::{Vec2, Vec3},
+    prelude::Resource,
+};
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
+
+use crate::{
+    components::{
+        crop::CropBundle, item::ItemStack, player::PlayerInventory, structure::StructureBundle,
+    },
+    configuration::{
+        errors::ConfigError,
+        game::GameConfiguration,
+        kdl_utils::{find_node, parse, trim},
+    },
+    rng::RngStream,
+};
+
+pub const SAVE_PATH: &str = "./saves/save.kdl";
+pub const SAVE_VERSION: i64 = 11;
+
+#[derive(Debug)]
+pub enum SaveError {
+    ParseError(ConfigError),
+    UnsupportedVersion(i64),
+    MissingNode(&'static str),
+    RngPositionTooLarge(u128),
+    WriteError(io::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParseError(e) => write!(f, "could not read the save ({e})"),
+            Self::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "save version {version} is not supported, expected {SAVE_VERSION}"
+                )
+            }
+            Self::MissingNode(name) => write!(f, "save has no `{name}` node"),
+            Self::RngPositionTooLarge(position) => {
+                write!(f, "rng position {position} is too large to save")
+            }
+            Self::WriteError(e) => write!(f, "could not write the save ({e})"),
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct LoadedSave(pub Option<SaveGame>);
+
+// Floors the player has left, by level, kept until they come back to them
+#[derive(Default, Resource)]
+pub struct VisitedFloors {
+    pub floors: HashMap<usize, FloorSave>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SaveGame {
+    pub seed: String,
+    pub level: usize,
+    pub ticks: u64,
+    pub player: PlayerSave,
+    pub floor: FloorSave,
+    pub visited_floors: Vec<FloorSave>,
+    pub rng: Vec<RngSave>,
+}
+
+// The position is where the player stood, so they arrive back there, and the ticks are the
+// world tick they left on, so crops can catch up on the growth they missed
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloorSave {
+    pub level: usize,
+    pub position: Vec2,
+    pub ticks: u64,
+    pub structures: Vec<StructureSave>,
+    pub crops: Vec<CropSave>,
+    pub items: Vec<ItemSave>,
+    pub explored: Vec<(i32, i32)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PlayerSave {
+    pub position: Vec2,
+    pub held_seeds: Vec<StackSave>,
+    pub held_tools: Vec<String>,
+    pub current_crop: Option<String>,
+    pub current_tool: Option<String>,
+    pub current_selected_index: Option<usize>,
+    pub produce: Vec<StackSave>,
+    pub held_items: Vec<StackSave>,
+    pub held_notes: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StackSave {
+    pub key: String,
+    pub quantity: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructureSave {
+    pub key: String,
+    pub position: Vec3,
+    pub current_health: i32,
+    pub moisture: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CropSave {
+    pub key: String,
+    pub position: Vec2,
+    pub current_stage_index: usize,
+    pub growth: u32,
+    pub neglected_ticks: u32,
+    pub withered: bool,
+    pub withered_ticks: u32,
+    pub stages: Vec<CropStageSave>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CropStageSave {
+    pub ticks_in_stage: u32,
+    pub min_ticks_in_stage: u32,
+}
+
+// How many words a floor's stream has used up, replaying that far lands on the same next roll
+#[derive(Debug, PartialEq)]
+pub struct RngSave {
+    pub floor_seed: String,
+    pub stream: RngStream,
+    pub position: u128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemSave {
+    pub key: String,
+    pub position: Vec3,
+    pub underground: bool,
+    pub quantity: u32,
+}
+
+impl SaveGame {
+    pub fn load(path: &str) -> Result<Self, SaveError> {
+        let nodes = parse(path).map_err(SaveError::ParseError)?;
+
+        let version = find_node(&nodes, "version")
+            .and_then(|node| match node.entries().first() {
+                Some(entry) => match entry.value() {
+                    KdlValue::Base10(it) => Some(*it),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .ok_or(SaveError::MissingNode("version"))?;
+
+        if version != SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(version));
+        }
+
+        let world_node = find_node(&nodes, "world").ok_or(SaveError::MissingNode("world"))?;
+        let player_node = find_node(&nodes, "player").ok_or(SaveError::MissingNode("player"))?;
+
+        let level = integer_value(world_node, "level").unwrap_or(0) as usize;
+        let ticks = integer_value(world_node, "ticks").unwrap_or(0) as u64;
+        let player = PlayerSave::from(player_node);
+        let floor = FloorSave::from_nodes(level, player.position, ticks, &nodes);
+
+        Ok(Self {
+            seed: string_value(world_node, "seed").unwrap_or_default(),
+            level,
+            ticks,
+            player,
+            floor,
+            visited_floors: nodes_named(&nodes, "floor").map(FloorSave::from).collect(),
+            rng: nodes_named(&nodes, "rng")
+                .filter_map(RngSave::from_node)
+                .collect(),
+        })
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), SaveError> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent).map_err(SaveError::WriteError)?;
+        }
+
+        let mut document = KdlDocument::new();
+        let nodes = document.nodes_mut();
+
+        let mut version = KdlNode::new("version");
+        version.push(KdlEntry::new(SAVE_VERSION));
+        nodes.push(version);
+
+        let mut world = KdlNode::new("world");
+        world.push(KdlEntry::new_prop("seed", self.seed.clone()));
+        world.push(KdlEntry::new_prop("level", self.level as i64));
+        world.push(KdlEntry::new_prop("ticks", self.ticks as i64));
+        nodes.push(world);
+
+        nodes.push(self.player.to_node());
+        nodes.extend(self.floor.to_nodes());
+        nodes.extend(self.visited_floors.iter().map(FloorSave::to_node));
+        for rng in self.rng.iter() {
+            nodes.push(rng.to_node()?);
+        }
+
+        document.fmt();
+        fs::write(path, document.to_string()).map_err(SaveError::WriteError)
+    }
+}
+
+impl FloorSave {
+    fn from_nodes(level: usize, position: Vec2, ticks: u64, nodes: &[KdlNode]) -> Self {
+        Self {
+            level,
+            position,
+            ticks,
+            structures: nodes_named(nodes, "structure")
+                .map(StructureSave::from)
+                .collect(),
+            crops: nodes_named(nodes, "crop").map(CropSave::from).collect(),
+            items: nodes_named(nodes, "item").map(ItemSave::from).collect(),
+            explored: nodes_named(nodes, "explored")
+                .flat_map(explored_row)
+                .collect(),
+        }
+    }
+
+    fn to_nodes(&self) -> Vec<KdlNode> {
+        let mut nodes = Vec::new();
+        nodes.extend(self.structures.iter().map(StructureSave::to_node));
+        nodes.extend(self.crops.iter().map(CropSave::to_node));
+        nodes.extend(self.items.iter().map(ItemSave::to_node));
+        nodes.extend(explored_rows(&self.explored));
+        nodes
+    }
+
+    fn to_node(&self) -> KdlNode {
+        let mut node = KdlNode::new("floor");
+        node.push(KdlEntry::new_prop("level", self.level as i64));
+        node.push(KdlEntry::new_prop("x", self.position.x as f64));
+        node.push(KdlEntry::new_prop("y", self.position.y as f64));
+        node.push(KdlEntry::new_prop("ticks", self.ticks as i64));
+
+        let mut children = KdlDocument::new();
+        children.nodes_mut().extend(self.to_nodes());
+        node.set_children(children);
+
+        node
+    }
+}
+
+impl From<&KdlNode> for FloorSave {
+    fn from(node: &KdlNode) -> Self {
+        let children: Vec<KdlNode> = node
+            .children()
+            .iter()
+            .flat_map(|doc| doc.nodes())
+            .cloned()
+            .collect();
+
+        Self::from_nodes(
+            integer_value(node, "level").unwrap_or(0) as usize,
+            Vec2::new(
+                float_value(node, "x").unwrap_or(0.0),
+                float_value(node, "y").unwrap_or(0.0),
+            ),
+            integer_value(node, "ticks").unwrap_or(0) as u64,
+            &children,
+        )
+    }
+}
+
+impl PlayerSave {
+    pub fn restore_inventory(&self, inventory: &mut PlayerInventory, config: &GameConfiguration) {
+        inventory.held_seeds.clear();
+        for seeds in self.held_seeds.iter() {
+            if let Some(crop_config) = config.crops_config.config_by_key(&seeds.key) {
+                inventory.add_seeds(crop_config, seeds.quantity, &config.items_config);
+            }
+        }
+        inventory.held_tools = self
+            .held_tools
+            .iter()
+            .filter_map(|key| config.tool_configs.tool_by_key(key))
+            .collect();
+        inventory.current_crop_config = self
+            .current_crop
+            .as_ref()
+            .and_then(|key| config.crops_config.config_by_key(key))
+            .cloned();
+        inventory.current_tool = self
+            .current_tool
+            .as_ref()
+            .and_then(|key| config.tool_configs.tool_by_key(key))
+            .map(|tool_config| tool_config.to_tool());
+        inventory.current_selected_index = self.current_selected_index;
+        inventory.held_produce.clear();
+        for produce in self.produce.iter() {
+            if let Some(crop_config) = config.crops_config.config_by_key(&produce.key) {
+                inventory.add_produce(crop_config, produce.quantity, &config.items_config);
+            }
+        }
+        inventory.held_items = self
+            .held_items
+            .iter()
+            .filter_map(|stack| {
+                Some(ItemStack {
+                    config: config.items_config.config_by_key(&stack.key)?.clone(),
+                    quantity: stack.quantity,
+                })
+            })
+            .collect();
+        inventory.held_notes = self
+            .held_notes
+            .iter()
+            .filter_map(|key| config.notes_config.config_by_key(key))
+            .cloned()
+            .collect();
+    }
+
+    fn to_node(&self) -> KdlNode {
+        let mut node = KdlNode::new("player");
+        node.push(KdlEntry::new_prop("x", self.position.x as f64));
+        node.push(KdlEntry::new_prop("y", self.position.y as f64));
+        if let Some(index) = self.current_selected_index {
+            node.push(KdlEntry::new_prop("selected_index", index as i64));
+        }
+
+        let mut children = KdlDocument::new();
+        let nodes = children.nodes_mut();
+        nodes.extend(self.held_seeds.iter().map(|seeds| seeds.to_node("seed")));
+        nodes.extend(self.held_tools.iter().map(|key| key_node("tool", key)));
+        if let Some(key) = &self.current_crop {
+            nodes.push(key_node("current_crop", key));
+        }
+        if let Some(key) = &self.current_tool {
+            nodes.push(key_node("current_tool", key));
+        }
+        nodes.extend(
+            self.produce
+                .iter()
+                .map(|produce| produce.to_node("produce")),
+        );
+        nodes.extend(
+            self.held_items
+                .iter()
+                .map(|stack| stack.to_node("held_item")),
+        );
+        nodes.extend(self.held_notes.iter().map(|key| key_node("note", key)));
+        node.set_children(children);
+
+        node
+    }
+}
+
+impl From<&KdlNode> for PlayerSave {
+    fn from(node: &KdlNode) -> Self {
+        let children: Vec<KdlNode> = node
+            .children()
+            .iter()
+            .flat_map(|doc| doc.nodes())
+            .cloned()
+            .collect();
+
+        let keys = |name: &str| -> Vec<String> {
+            nodes_named(&children, name)
+                .filter_map(|child| string_value(child, "key"))
+                .collect()
+        };
+
+        Self {
+            position: Vec2::new(
+                float_value(node, "x").unwrap_or(0.0),
+                float_value(node, "y").unwrap_or(0.0),
+            ),
+            held_seeds: nodes_named(&children, "seed")
+                .filter_map(StackSave::from_node)
+                .collect(),
+            held_tools: keys("tool"),
+            current_crop: keys("current_crop").into_iter().next(),
+            current_tool: keys("current_tool").into_iter().next(),
+            current_selected_index: integer_value(node, "selected_index").map(|it| it as usize),
+            produce: nodes_named(&children, "produce")
+                .filter_map(StackSave::from_node)
+                .collect(),
+            held_items: nodes_named(&children, "held_item")
+                .filter_map(StackSave::from_node)
+                .collect(),
+            held_notes: keys("note"),
+        }
+    }
+}
+
+impl StackSave {
+    fn to_node(&self, name: &str) -> KdlNode {
+        let mut node = key_node(name, &self.key);
+        node.push(KdlEntry::new_prop("quantity", self.quantity as i64));
+        node
+    }
+
+    fn from_node(node: &KdlNode) -> Option<Self> {
+        Some(Self {
+            key: string_value(node, "key")?,
+            quantity: integer_value(node, "quantity").unwrap_or(1) as u32,
+        })
+    }
+}
+
+impl StructureSave {
+    pub fn apply(&self, bundle: StructureBundle) -> StructureBundle {
+        bundle.with_current_health(self.current_health)
+    }
+
+    fn to_node(&self) -> KdlNode {
+        let mut node = key_node("structure", &self.key);
+        push_position(&mut node, self.position);
+        node.push(KdlEntry::new_prop("health", self.current_health as i64));
+        if let Some(moisture) = self.moisture {
+            node.push(KdlEntry::new_prop("moisture", moisture as i64));
+        }
+        node
+    }
+}
+
+impl From<&KdlNode> for StructureSave {
+    fn from(node: &KdlNode) -> Self {
+        Self {
+            key: string_value(node, "key").unwrap_or_default(),
+            position: position_value(node),
+            current_health: integer_value(node, "health").unwrap_or(0) as i32,
+            moisture: integer_value(node, "moisture").map(|it| it as u32),
+        }
+    }
+}
+
+impl CropSave {
+    pub fn apply(&self, bundle: &mut CropBundle) {
+        for (stage, saved_stage) in bundle.stages.stages.iter_mut().zip(self.stages.iter()) {
+            stage.ticks_in_stage = saved_stage.ticks_in_stage;
+            stage.min_ticks_in_stage = saved_stage.min_ticks_in_stage;
+        }
+
+        if self.current_stage_index < bundle.stages.stages.len() {
+            bundle.crop.current_stage_index = self.current_stage_index;
+        }
+
+        bundle.crop.growth = self.growth;
+        bundle.crop.neglected_ticks = self.neglected_ticks;
+        bundle.crop.withered = self.withered;
+        bundle.crop.withered_ticks = self.withered_ticks;
+        if let Some(sprite_index) = bundle.crop.sprite_index(&bundle.stages) {
+            bundle.sprite.sprite.index = sprite_index;
+        }
+    }
+
+    fn to_node(&self) -> KdlNode {
+        let mut node = key_node("crop", &self.key);
+        node.push(KdlEntry::new_prop("x", self.position.x as f64));
+        node.push(KdlEntry::new_prop("y", self.position.y as f64));
+        node.push(KdlEntry::new_prop("stage", self.current_stage_index as i64));
+        node.push(KdlEntry::new_prop("growth", self.growth as i64));
+        node.push(KdlEntry::new_prop(
+            "neglected_ticks",
+            self.neglected_ticks as i64,
+        ));
+        node.push(KdlEntry::new_prop("withered", self.withered));
+        node.push(KdlEntry::new_prop(
+            "withered_ticks",
+            self.withered_ticks as i64,
+        ));
+
+        let mut children = KdlDocument::new();
+        children.nodes_mut().extend(self.stages.iter().map(|stage| {
+            let mut stage_node = KdlNode::new("stage");
+            stage_node.push(KdlEntry::new_prop("ticks", stage.ticks_in_stage as i64));
+            stage_node.push(KdlEntry::new_prop(
+                "min_ticks",
+                stage.min_ticks_in_stage as i64,
+            ));
+            stage_node
+        }));
+        node.set_children(children);
+
+        node
+    }
+}
+
+impl From<&KdlNode> for CropSave {
+    fn from(node: &KdlNode) -> Self {
+        let stages = node
+            .children()
+            .iter()
+            .flat_map(|doc| doc.nodes())
+            .map(|stage_node| CropStageSave {
+                ticks_in_stage: integer_value(stage_node, "ticks").unwrap_or(0) as u32,
+                min_ticks_in_stage: integer_value(stage_node, "min_ticks").unwrap_or(0) as u32,
+            })
+            .collect();
+
+        Self {
+            key: string_value(node, "key").unwrap_or_default(),
+            position: Vec2::new(
+                float_value(node, "x").unwrap_or(0.0),
+                float_value(node, "y").unwrap_or(0.0),
+            ),
+            current_stage_index: integer_value(node, "stage").unwrap_or(0) as usize,
+            growth: integer_value(node, "growth").unwrap_or(0) as u32,
+            neglected_ticks: integer_value(node, "neglected_ticks").unwrap_or(0) as u32,
+            withered: bool_value(node, "withered").unwrap_or(false),
+            withered_ticks: integer_value(node, "withered_ticks").unwrap_or(0) as u32,
+            stages,
+        }
+    }
+}
+
+impl RngSave {
+    fn to_node(&self) -> Result<KdlNode, SaveError> {
+        let position = i64::try_from(self.position)
+            .map_err(|_| SaveError::RngPositionTooLarge(self.position))?;
+
+        let mut node = KdlNode::new("rng");
+        node.push(KdlEntry::new_prop("floor", self.floor_seed.clone()));
+        node.push(KdlEntry::new_prop("stream", self.stream.key()));
+        node.push(KdlEntry::new_prop("position", position));
+        Ok(node)
+    }
+
+    fn from_node(node: &KdlNode) -> Option<Self> {
+        Some(Self {
+            floor_seed: string_value(node, "floor")?,
+            stream: string_value(node, "stream")?.parse().ok()?,
+            position: u128::try_from(integer_value(node, "position")?).ok()?,
+        })
+    }
+}
+
+impl ItemSave {
+    fn to_node(&self) -> KdlNode {
+        let mut node = key_node("item", &self.key);
+        push_position(&mut node, self.position);
+        node.push(KdlEntry::new_prop("underground", self.underground));
+        node.push(KdlEntry::new_prop("quantity", self.quantity as i64));
+        node
+    }
+}
+
+impl From<&KdlNode> for ItemSave {
+    fn from(node: &KdlNode) -> Self {
+        Self {
+            key: string_value(node, "key").unwrap_or_default(),
+            position: position_value(node),
+            underground: bool_value(node, "underground").unwrap_or(false),
+            quantity: integer_value(node, "quantity").unwrap_or(1) as u32,
+        }
+    }
+}
+
+// Explored tiles go in one node per row, `explored y=3 4 5 9` for tiles 4, 5 and 9 of row 3
+fn explored_rows(tiles: &[(i32, i32)]) -> Vec<KdlNode> {
+    let mut rows: Vec<KdlNode> = Vec::new();
+    let mut current_row = None;
+    for (x, y) in tiles.iter() {
+        if current_row != Some(*y) {
+            let mut row = KdlNode::new("explored");
+            row.push(KdlEntry::new_prop("y", *y as i64));
+            rows.push(row);
+            current_row = Some(*y);
+        }
+
+        if let Some(row) = rows.last_mut() {
+            row.push(KdlEntry::new(*x as i64));
+        }
+    }
+
+    rows
+}
+
+fn explored_row(node: &KdlNode) -> Vec<(i32, i32)> {
+    let y = match integer_value(node, "y") {
+        Some(it) => it as i32,
+        _ => return Vec::new(),
+    };
+
+    node.entries()
+        .iter()
+        .filter(|entry| entry.name().is_none())
+        .filter_map(|entry| match entry.value() {
+            KdlValue::Base10(x) => Some((*x as i32, y)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn nodes_named<'a>(nodes: &'a [KdlNode], name: &'a str) -> impl Iterator<Item = &'a KdlNode> {
+    nodes
+        .iter()
+        .filter(move |node| node.name().value().eq_ignore_ascii_case(name))
+}
+
+fn key_node(name: &str, key: &str) -> KdlNode {
+    let mut node = KdlNode::new(name);
+    node.push(KdlEntry::new_prop("key", key));
+    node
+}
+
+fn push_position(node: &mut KdlNode, position: Vec3) {
+    node.push(KdlEntry::new_prop("x", position.x as f64));
+    node.push(KdlEntry::new_prop("y", position.y as f64));
+    node.push(KdlEntry::new_prop("z", position.z as f64));
+}
+
+fn position_value(node: &KdlNode) -> Vec3 {
+    Vec3::new(
+        float_value(node, "x").unwrap_or(0.0),
+        float_value(node, "y").unwrap_or(0.0),
+        float_value(node, "z").unwrap_or(0.0),
+    )
+}
+
+fn string_value(node: &KdlNode, key: &str) -> Option<String> {
+    match node.get(key) {
+        Some(entry) => match entry.value() {
+            KdlValue::RawString(it) | KdlValue::String(it) => Some(trim(it.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn integer_value(node: &KdlNode, key: &str) -> Option<i64> {
+    match node.get(key) {
+        Some(entry) => match entry.value() {
+            KdlValue::Base10(it) => Some(*it),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn bool_value(node: &KdlNode, key: &str) -> Option<bool> {
+    match node.get(key) {
+        Some(entry) => match entry.value() {
+            KdlValue::Bool(it) => Some(*it),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn float_value(node: &KdlNode, key: &str) -> Option<f32> {
+    match node.get(key) {
+        Some(entry) => match entry.value() {
+            KdlValue::Base10Float(it) => Some(*it as f32),
+            KdlValue::Base10(it) => Some(*it as f32),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::{Vec2, Vec3};
+
+    use crate::rng::RngStream;
+
+    use super::{
+        CropSave, CropStageSave, FloorSave, ItemSave, PlayerSave, RngSave, SaveError, SaveGame,
+        StackSave, StructureSave,
+    };
+
+    fn save_game() -> SaveGame {
+        SaveGame {
+            seed: "cave".to_string(),
+            level: 3,
+            ticks: 412,
+            player: PlayerSave {
+                position: Vec2::new(24.0, -40.5),
+                held_seeds: vec![
+                    StackSave {
+                        key: "kane".to_string(),
+                        quantity: 4,
+                    },
+                    StackSave {
+                        key: "potato".to_string(),
+                        quantity: 1,
+                    },
+                ],
+                held_tools: vec!["rusty_pickaxe".to_string()],
+                current_crop: Some("kane".to_string()),
+                current_tool: Some("rusty_pickaxe".to_string()),
+                current_selected_index: Some(1),
+                produce: vec![StackSave {
+                    key: "potato".to_string(),
+                    quantity: 12,
+                }],
+                held_items: vec![StackSave {
+                    key: "sand".to_string(),
+                    quantity: 30,
+                }],
+                held_notes: vec!["farmers_lament".to_string()],
+            },
+            floor: FloorSave {
+                level: 3,
+                position: Vec2::new(24.0, -40.5),
+                ticks: 412,
+                structures: vec![
+                    StructureSave {
+                        key: "room_wall".to_string(),
+                        position: Vec3::new(32.0, 48.0, 1.0),
+                        current_health: 14,
+                        moisture: None,
+                    },
+                    StructureSave {
+                        key: "table".to_string(),
+                        position: Vec3::new(-64.0, 16.0, 1.0),
+                        current_health: 0,
+                        moisture: None,
+                    },
+                    StructureSave {
+                        key: "tilled_soil".to_string(),
+                        position: Vec3::new(16.0, 16.0, 1.0),
+                        current_health: 1000,
+                        moisture: Some(6),
+                    },
+                ],
+                crops: vec![
+                    CropSave {
+                        key: "kane".to_string(),
+                        position: Vec2::new(16.0, 16.0),
+                        current_stage_index: 1,
+                        growth: 35,
+                        neglected_ticks: 0,
+                        withered_ticks: 0,
+                        withered: false,
+                        stages: vec![
+                            CropStageSave {
+                                ticks_in_stage: 22,
+                                min_ticks_in_stage: 15,
+                            },
+                            CropStageSave {
+                                ticks_in_stage: 8,
+                                min_ticks_in_stage: 34,
+                            },
+                        ],
+                    },
+                    CropSave {
+                        key: "potato".to_string(),
+                        position: Vec2::new(-16.0, 32.0),
+                        current_stage_index: 0,
+                        growth: 0,
+                        neglected_ticks: 30,
+                        withered_ticks: 12,
+                        withered: true,
+                        stages: vec![CropStageSave {
+                            ticks_in_stage: 40,
+                            min_ticks_in_stage: 20,
+                        }],
+                    },
+                ],
+                items: vec![
+                    ItemSave {
+                        key: "bone_shovel".to_string(),
+                        position: Vec3::new(64.0, -8.0, 0.5),
+                        underground: false,
+                        quantity: 1,
+                    },
+                    ItemSave {
+                        key: "stone_hoe".to_string(),
+                        position: Vec3::new(80.0, 96.0, 0.5),
+                        underground: true,
+                        quantity: 1,
+                    },
+                    ItemSave {
+                        key: "potatoes".to_string(),
+                        position: Vec3::new(-32.0, 0.0, 0.5),
+                        underground: false,
+                        quantity: 7,
+                    },
+                ],
+                explored: vec![(0, 0), (1, 0), (2, 0), (1, 1)],
+            },
+            visited_floors: vec![FloorSave {
+                level: 2,
+                position: Vec2::new(-8.0, 120.0),
+                ticks: 180,
+                structures: vec![StructureSave {
+                    key: "ladder".to_string(),
+                    position: Vec3::new(-8.0, 120.0, 1.0),
+                    current_health: 1000,
+                    moisture: None,
+                }],
+                crops: Vec::new(),
+                items: Vec::new(),
+                explored: vec![(3, 7), (4, 7), (-1, 8)],
+            }],
+            rng: vec![
+                RngSave {
+                    floor_seed: "cave-3".to_string(),
+                    stream: RngStream::Items,
+                    position: 48,
+                },
+                RngSave {
+                    floor_seed: "cave-3".to_string(),
+                    stream: RngStream::Crops,
+                    position: 1_024,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn a_written_save_loads_back_the_same() {
+        let path = std::env::temp_dir().join("under_farm_round_trip.kdl");
+        let path = path.to_str().unwrap();
+        let save = save_game();
+
+        save.write(path).unwrap();
+        let loaded = SaveGame::load(path).unwrap();
+
+        assert_eq!(loaded, save);
+    }
+    #[test]
+    fn an_rng_position_past_i64_is_not_written() {
+        let path = std::env::temp_dir().join("under_farm_rng_overflow.kdl");
+        let mut save = save_game();
+        save.rng[0].position = i64::MAX as u128 + 1;
+
+        let result = save.write(path.to_str().unwrap());
+
+        assert!(matches!(result, Err(SaveError::RngPositionTooLarge(_))));
+    }
+}
@@ -6,6 +6,7 @@ pub enum AppState {
     FinishedLoading,
     InGame,
     InventoryScreen,
+    ReadingNote,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Resource)]
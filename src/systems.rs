@@ -1,11 +1,19 @@
 pub mod actions;
 pub mod cameras;
+pub mod config_reload;
+pub mod controls;
 pub mod crops;
+pub mod floors;
+pub mod fov;
 pub mod initial_spawns;
 pub mod inputs;
 pub mod inventory;
 pub mod loading;
+pub mod map_view;
 pub mod movement;
+pub mod notes;
+pub mod occupancy;
+pub mod save;
 pub mod spawns;
 pub mod textures;
 pub mod world;
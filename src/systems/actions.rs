@@ -1,18 +1,31 @@
 use bevy::{
     math::{Vec2, Vec3},
-    prelude::{Commands, Mut, Query, Transform},
+    prelude::{Commands, Mut, Query, Res, ResMut, Transform, Visibility},
     sprite::TextureAtlasSprite,
 };
-
-use crate::components::{
-    action::{CurrentAction, InteractAction},
-    body::Body,
-    bounding_box::BoundingBox,
-    crop::{Crop, CropSpawn},
-    item::{Item, ItemType},
-    player::{Player, PlayerInventory},
-    spawns::Spawns,
-    structure::{Structure, StructureSpawn},
+use rand::Rng;
+
+use crate::{
+    components::{
+        action::{CurrentAction, InteractAction},
+        body::Body,
+        crop::{Crop, CropSpawn},
+        ground::GroundTile,
+        item::{Item, ItemType},
+        moisture::SoilMoisture,
+        note::NoteReader,
+        player::{Player, PlayerInventory},
+        spawns::Spawns,
+        structure::{Structure, StructureSpawn},
+    },
+    configuration::{
+        controls::InputAction, game::GameConfiguration, map::grid_coordinate_from_world,
+    },
+    rng::{GameRng, RngStream},
+    systems::{
+        crops::{can_plant_at, neighbouring_tiles},
+        occupancy::TileOccupancy,
+    },
 };
 
 pub fn hit_actions(
@@ -46,7 +59,9 @@ pub fn hit_actions(
 pub fn pickup_actions(
     mut commands: Commands,
     mut player_query: Query<(&Player, &CurrentAction, &mut PlayerInventory)>,
-    item_query: Query<&Item>,
+    mut item_query: Query<&mut Item>,
+    mut reader_query: Query<&mut NoteReader>,
+    game_config: Res<GameConfiguration>,
 ) {
     if player_query.is_empty() {
         return;
@@ -60,12 +75,12 @@ pub fn pickup_actions(
         _ => return,
     };
 
-    let item: &Item = match item_query.get(pickup.target) {
+    let mut item: Mut<Item> = match item_query.get_mut(pickup.target) {
         Ok(it) => it,
         _ => return,
     };
 
-    match &item.item_type {
+    match &mut item.item_type {
         ItemType::Tool(tool) => {
             if !player_inventory
                 .held_tools
@@ -76,6 +91,34 @@ pub fn pickup_actions(
                 player_inventory.held_tools.push(tool.clone())
             }
         }
+        ItemType::Stack(stack) => {
+            let taken = player_inventory.add_item(
+                &stack.config,
+                stack.quantity,
+                &game_config.crops_config,
+                &game_config.items_config,
+            );
+
+            // Whatever doesn't fit in the inventory stays on the ground
+            stack.quantity -= taken;
+            if stack.quantity == 0 {
+                commands.entity(pickup.target).despawn();
+            }
+        }
+        ItemType::Note(note) => {
+            let index = player_inventory.add_note(note);
+            commands.entity(pickup.target).despawn();
+            println!(
+                "Picked up {}, press {} to read it",
+                note.title,
+                game_config.controls_config.display(InputAction::ReadNotes)
+            );
+
+            if !reader_query.is_empty() {
+                let mut reader: Mut<NoteReader> = reader_query.single_mut();
+                reader.index = index;
+            }
+        }
     }
 }
 
@@ -91,6 +134,107 @@ pub fn clear_structure_action(mut commands: Commands, query: Query<(&Player, &Cu
     }
 }
 
+pub fn water_action(
+    query: Query<(&Player, &CurrentAction, &PlayerInventory)>,
+    mut moisture_query: Query<&mut SoilMoisture>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let (_, action, inventory): (&Player, &CurrentAction, &PlayerInventory) = query.single();
+
+    let water_action = match &action.interact {
+        Some(InteractAction::WaterAction(it)) => it,
+        _ => return,
+    };
+
+    let water = match &inventory.current_tool {
+        Some(tool) => tool.water,
+        _ => return,
+    };
+
+    let mut moisture: Mut<SoilMoisture> = match moisture_query.get_mut(water_action.entity) {
+        Ok(it) => it,
+        _ => return,
+    };
+
+    moisture.water(water);
+}
+
+pub fn harvest_action(
+    mut commands: Commands,
+    mut query: Query<(&Player, &CurrentAction, &mut PlayerInventory)>,
+    crop_query: Query<(&Crop, &Transform)>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let (_, action, mut inventory): (&Player, &CurrentAction, Mut<PlayerInventory>) =
+        query.single_mut();
+
+    let harvest = match &action.interact {
+        Some(InteractAction::Harvest(it)) => it,
+        _ => return,
+    };
+
+    let (crop, transform): (&Crop, &Transform) = match crop_query.get(harvest.entity) {
+        Ok(it) => it,
+        _ => return,
+    };
+
+    if crop.withered {
+        commands.entity(harvest.entity).despawn();
+        return;
+    }
+
+    if !crop.is_mature() {
+        return;
+    }
+
+    // Anything past a full stack is left behind with the plant
+    inventory.add_produce(
+        &crop.config,
+        crop.config.harvest.produce,
+        &game_config.items_config,
+    );
+    inventory.add_seeds(
+        &crop.config,
+        crop.config.harvest.seeds,
+        &game_config.items_config,
+    );
+
+    // Any mature neighbour a recipe crosses this crop with can also give a hybrid seed
+    let neighbours: Vec<&Crop> = neighbouring_tiles(transform.translation.truncate(), &game_config)
+        .into_iter()
+        .flat_map(|(_, coordinate)| occupancy.crops_at(&coordinate))
+        .filter_map(|entity| crop_query.get(*entity).ok())
+        .map(|(neighbour, _)| neighbour)
+        .filter(|neighbour| neighbour.is_mature() && !neighbour.withered)
+        .collect();
+    let rng = game_rng.stream(&game_config, RngStream::Crops);
+    for hybrid in game_config.crops_config.configurations.iter() {
+        let recipe = match &hybrid.breeding {
+            Some(it) => it,
+            _ => continue,
+        };
+
+        if neighbours
+            .iter()
+            .any(|neighbour| recipe.crosses(&crop.config.key, &neighbour.config.key))
+            && rng.gen_range(0..100) < recipe.chance
+        {
+            inventory.add_seeds(hybrid, 1, &game_config.items_config);
+        }
+    }
+
+    commands.entity(harvest.entity).despawn();
+}
+
 pub fn reset_pickup_actions(mut query: Query<(&Player, &mut CurrentAction)>) {
     if query.is_empty() {
         return;
@@ -109,7 +253,14 @@ pub fn reset_hit_actions(mut query: Query<(&Player, &mut CurrentAction)>) {
     current_action.hit = None;
 }
 
-pub fn dig_action(query: Query<(&Player, &CurrentAction)>, mut spawns_query: Query<&mut Spawns>) {
+pub fn dig_action(
+    query: Query<(&Player, &CurrentAction)>,
+    mut spawns_query: Query<&mut Spawns>,
+    mut item_query: Query<(&Item, &mut Body, &mut Visibility)>,
+    ground_query: Query<(&GroundTile, &Transform)>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
+) {
     if query.is_empty() {
         return;
     }
@@ -125,57 +276,68 @@ pub fn dig_action(query: Query<(&Player, &CurrentAction)>, mut spawns_query: Que
         return;
     }
 
+    // Digging unearths anything hidden at the spot
+    let coordinate = grid_coordinate_from_world(
+        &dig_action.position,
+        game_config.map_size(),
+        game_config.tile_size(),
+    );
+    for entity in occupancy.items_at(&coordinate) {
+        let (_, mut body, mut visibility): (&Item, Mut<Body>, Mut<Visibility>) =
+            match item_query.get_mut(*entity) {
+                Ok(it) => it,
+                _ => continue,
+            };
+
+        if body.underground {
+            body.underground = false;
+            visibility.is_visible = true;
+        }
+    }
+
+    let tillable = occupancy
+        .ground_on_top(&coordinate, &ground_query)
+        .is_some_and(|ground| ground.tillable);
+
     let mut spawns: Mut<Spawns> = spawns_query.single_mut();
 
     spawns.structures.push(StructureSpawn {
         position: Vec3::new(dig_action.position.x, dig_action.position.y, 1.),
-        structure_key: "dug_spot",
+        structure_key: if tillable { "tilled_soil" } else { "dug_spot" },
     });
 }
 
 pub fn crop_actions(
-    query: Query<(&Player, &CurrentAction, &PlayerInventory)>,
-    crop_query: Query<(&Crop, &Transform)>,
-    structure_query: Query<(&Structure, &Body)>,
+    mut query: Query<(&Player, &CurrentAction, &mut PlayerInventory)>,
+    structure_query: Query<&Structure>,
     mut spawns_query: Query<&mut Spawns>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
 ) {
     if query.is_empty() {
         return;
     }
 
-    let (_, action, inventory): (&Player, &CurrentAction, &PlayerInventory) = query.single();
+    let (_, action, mut inventory): (&Player, &CurrentAction, Mut<PlayerInventory>) =
+        query.single_mut();
 
     let plant_action = match &action.interact {
         Some(InteractAction::PlantCrop(it)) => it,
         _ => return,
     };
 
-    let planting_bounds =
-        BoundingBox::square(plant_action.position.x, plant_action.position.y, 60.0);
-
-    for crop_data in crop_query.iter() {
-        let (_, crop_transform): (&Crop, &Transform) = crop_data;
-        let crop_bounds = BoundingBox::square(
-            crop_transform.translation.x.floor(),
-            crop_transform.translation.y.floor(),
-            60.0,
-        );
-
-        if crop_bounds.intersects(&planting_bounds) {
-            return;
-        }
-    }
-
-    for structure_data in structure_query.iter() {
-        let (_, body): (&Structure, &Body) = structure_data;
+    let coordinate = grid_coordinate_from_world(
+        &plant_action.position,
+        game_config.map_size(),
+        game_config.tile_size(),
+    );
 
-        if body.intersects_box(&planting_bounds) {
-            return;
-        }
+    if !can_plant_at(&coordinate, &occupancy, &structure_query) {
+        return;
     }
 
     let config = match &inventory.current_crop_config {
-        Some(it) => it,
+        Some(it) => it.clone(),
         _ => return,
     };
 
@@ -183,10 +345,14 @@ pub fn crop_actions(
         return;
     }
 
+    if !inventory.consume_current_seed() {
+        return;
+    }
+
     let mut spawns = spawns_query.single_mut();
 
     spawns.crops.push(CropSpawn {
-        config: config.clone(),
+        config,
         location: Vec2::new(plant_action.position.x, plant_action.position.y),
     });
 }
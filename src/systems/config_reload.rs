@@ -0,0 +1,144 @@
+use std::{
+    fs,
+    marker::PhantomData,
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::{AssetServer, Assets, Query, Res, ResMut, Resource},
+    sprite::TextureAtlas,
+    time::{Time, Timer},
+};
+
+use crate::{
+    components::world::WorldTickTimer,
+    configuration::{
+        game::{GameConfiguration, ASSET_PATH, CONFIG_PATH},
+        timers::config_reload_timer,
+        validation::validate,
+    },
+    sprites::Sprites,
+    states::GameLoadState,
+};
+
+use super::{inputs::MovementInputTimer, textures::resolve_sprite_indices};
+
+#[derive(Resource)]
+pub struct ConfigWatcher {
+    pub timer: Timer,
+    pub last_modified: Option<SystemTime>,
+}
+
+impl Default for ConfigWatcher {
+    fn default() -> Self {
+        Self {
+            timer: config_reload_timer(),
+            last_modified: latest_modified(CONFIG_PATH),
+        }
+    }
+}
+
+fn latest_modified(path: &str) -> Option<SystemTime> {
+    fs::read_dir(path)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "kdl"))
+        .filter_map(|entry| entry.metadata().and_then(|it| it.modified()).ok())
+        .max()
+}
+
+// What sprite paths in a reloaded config are resolved against
+#[derive(SystemParam)]
+pub struct SpriteLookup<'w, 's> {
+    sprites: Res<'w, Sprites>,
+    texture_atlases: Res<'w, Assets<TextureAtlas>>,
+    asset_server: Res<'w, AssetServer>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> SpriteLookup<'w, 's> {
+    fn texture_atlas(&self) -> Option<&TextureAtlas> {
+        self.texture_atlases.get(&self.sprites.atlas_handle)
+    }
+}
+
+pub fn reload_config_system(
+    time: Res<Time>,
+    mut watcher: ResMut<ConfigWatcher>,
+    load_state: Res<GameLoadState>,
+    sprite_lookup: SpriteLookup,
+    mut game_config: ResMut<GameConfiguration>,
+    mut world_timer_query: Query<&mut WorldTickTimer>,
+    mut movement_timer: ResMut<MovementInputTimer>,
+) {
+    watcher.timer.tick(time.delta());
+    if !watcher.timer.just_finished() || !load_state.textures_set {
+        return;
+    }
+
+    let last_modified = latest_modified(CONFIG_PATH);
+    if last_modified == watcher.last_modified {
+        return;
+    }
+    watcher.last_modified = last_modified;
+
+    let mut new_config = match GameConfiguration::load(CONFIG_PATH) {
+        Ok(it) => it,
+        Err(errors) => {
+            println!("Not reloading configuration from {CONFIG_PATH}");
+            for error in errors.iter() {
+                println!("{error}");
+            }
+            return;
+        }
+    };
+
+    let validation_errors = validate(&new_config, ASSET_PATH);
+    if !validation_errors.is_empty() {
+        println!("Not reloading configuration from {CONFIG_PATH}");
+        for error in validation_errors.iter() {
+            println!("{error}");
+        }
+        return;
+    }
+
+    // The live floor, its tile index and the stashed floors were all laid out with the old values
+    if new_config.world_config != game_config.world_config
+        || new_config.tile_size() != game_config.tile_size()
+    {
+        println!(
+            "Not reloading configuration from {CONFIG_PATH}, restart to change world generation"
+        );
+        return;
+    }
+
+    let texture_atlas = match sprite_lookup.texture_atlas() {
+        Some(it) => it,
+        _ => return,
+    };
+
+    // The atlas is only built once at startup, so sprites added since then can't be used yet
+    let unresolved =
+        resolve_sprite_indices(&mut new_config, texture_atlas, &sprite_lookup.asset_server);
+    if !unresolved.is_empty() {
+        println!("Not reloading configuration from {CONFIG_PATH}, restart to load new sprites");
+        for sprite in unresolved.iter() {
+            println!("{sprite} is not in the texture atlas");
+        }
+        return;
+    }
+
+    new_config.restore_level(game_config.seed.clone(), game_config.level());
+    *game_config = new_config;
+    let tick_duration = game_config.world_tick_timer().duration();
+    for mut world_timer in world_timer_query.iter_mut() {
+        world_timer.0.set_duration(tick_duration);
+    }
+    movement_timer.0.set_duration(Duration::from_secs_f32(
+        game_config.controls_config.repeat_delay,
+    ));
+
+    println!("Reloaded configuration from {CONFIG_PATH}");
+}
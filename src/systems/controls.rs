@@ -0,0 +1,68 @@
+use bevy::{
+    input::{Axis, Input},
+    prelude::{
+        Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, Gamepads, KeyCode, Res, ResMut,
+    },
+};
+
+use crate::configuration::{controls::InputAction, game::GameConfiguration};
+
+// Rebuilt every frame from the bound keys, buttons and the left stick, so gameplay systems only
+// ever ask about actions and the keyboard and a gamepad can be used at the same time
+pub fn update_input_actions(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    game_config: Res<GameConfiguration>,
+    mut actions: ResMut<Input<InputAction>>,
+) {
+    actions.clear();
+
+    let controls = &game_config.controls_config;
+    let sticks: Vec<(f32, f32)> = gamepads
+        .iter()
+        .map(|gamepad| left_stick(gamepad, &gamepad_axes))
+        .collect();
+
+    for action in InputAction::ALL {
+        let key_held = controls
+            .key_codes(action)
+            .any(|key_code| keyboard_input.pressed(key_code));
+        let button_held = controls.buttons(action).any(|button_type| {
+            gamepad_buttons
+                .get_pressed()
+                .any(|button| button.button_type == button_type)
+        });
+        let stick_held = sticks
+            .iter()
+            .any(|(x, y)| stick_holds(action, *x, *y, controls.stick_threshold));
+
+        if key_held || button_held || stick_held {
+            actions.press(action);
+        } else {
+            actions.release(action);
+        }
+    }
+}
+
+fn left_stick(gamepad: Gamepad, gamepad_axes: &Axis<GamepadAxis>) -> (f32, f32) {
+    let x = gamepad_axes
+        .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+        .unwrap_or(0.0);
+    let y = gamepad_axes
+        .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+        .unwrap_or(0.0);
+
+    (x, y)
+}
+
+fn stick_holds(action: InputAction, x: f32, y: f32, threshold: f32) -> bool {
+    match action {
+        InputAction::MoveNorth | InputAction::SelectPrevious => y > threshold,
+        InputAction::MoveSouth | InputAction::SelectNext => y < -threshold,
+        InputAction::MoveEast | InputAction::NextPage => x > threshold,
+        InputAction::MoveWest | InputAction::PreviousPage => x < -threshold,
+        _ => false,
+    }
+}
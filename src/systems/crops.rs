@@ -1,28 +1,46 @@
+use std::collections::{HashMap, HashSet};
+
 use bevy::{
-    math::Vec2,
-    prelude::{Commands, Entity, Mut, Query, Transform},
+    math::{Vec2, Vec3},
+    prelude::{Added, Commands, Entity, Mut, Query, Res, ResMut, Transform, Without},
     sprite::TextureAtlasSprite,
 };
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
+use tdlg::map::cells::Coordinate;
 
 use crate::{
     components::{
-        crop::{Crop, CropSpawn, CropStages},
-        spawns::Spawns, world::World,
+        crop::{Crop, CropSpawn, CropStages, GrowingConditions},
+        ground::GroundTile,
+        moisture::SoilMoisture,
+        spawns::Spawns,
+        structure::{Structure, StructureSpawn},
+        world::World,
     },
+    configuration::{floors::SoilConfig, game::GameConfiguration, map::grid_coordinate_from_world},
+    rng::{GameRng, RngStream},
+    systems::occupancy::TileOccupancy,
 };
 
+type CropQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Crop,
+        &'static mut CropStages,
+        &'static mut TextureAtlasSprite,
+        &'static Transform,
+    ),
+>;
+
 pub fn grow_crops_system(
-    mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &Transform,
-        &mut Crop,
-        &mut CropStages,
-        &mut TextureAtlasSprite,
-    )>,
-    mut spawns_query: Query<&mut Spawns>,
+    mut query: CropQuery,
     world_query: Query<&World>,
+    moisture_query: Query<&SoilMoisture>,
+    ground_query: Query<(&GroundTile, &Transform)>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     if world_query.is_empty() {
         return;
@@ -34,51 +52,218 @@ pub fn grow_crops_system(
         return;
     }
 
-    let mut rng = rand::thread_rng();
+    let rng = game_rng.stream(&game_config, RngStream::Crops);
     for crop_data in query.iter_mut() {
-        let (entity, transform, mut crop, mut stages, mut sprite): (
-            Entity,
-            &Transform,
+        let (mut crop, mut stages, mut sprite, transform): (
             Mut<Crop>,
             Mut<CropStages>,
             Mut<TextureAtlasSprite>,
+            &Transform,
         ) = crop_data;
 
-        let stage = match stages.stages.get_mut(crop.current_stage_index) {
-            Some(it) => it,
-            _ => {
-                println!("No stage!");
-                continue;
-            }
+        let coordinate = grid_coordinate_from_world(
+            &transform.translation.truncate(),
+            game_config.map_size(),
+            game_config.tile_size(),
+        );
+        let moisture = occupancy
+            .structures_at(&coordinate)
+            .iter()
+            .find_map(|entity| moisture_query.get(*entity).ok())
+            .map_or(0, SoilMoisture::percent);
+        let soil = soil_at(&coordinate, &occupancy, &ground_query);
+        let conditions = GrowingConditions {
+            moisture,
+            growth_rate: crop.config.growth_rate(&soil),
         };
 
-        stage.ticks_in_stage += 1;
-        if stage.ticks_in_stage < stage.min_ticks_in_stage {
+        if crop.grow(&mut stages, conditions, rng) {
+            if let Some(sprite_index) = crop.sprite_index(&stages) {
+                sprite.index = sprite_index;
+            }
+        }
+    }
+}
+
+// Dead crops leave a dead plant behind that has to be cleared before the soil can be replanted
+pub fn clear_dead_crops(
+    mut commands: Commands,
+    query: Query<(Entity, &Crop, &Transform)>,
+    mut spawns_query: Query<&mut Spawns>,
+) {
+    if spawns_query.is_empty() {
+        return;
+    }
+
+    let mut spawns: Mut<Spawns> = spawns_query.single_mut();
+
+    for (entity, crop, transform) in query.iter() {
+        if !crop.is_dead() {
             continue;
         }
 
-        let chance_to_grow: u32 = rng.gen_range(1..100);
+        commands.entity(entity).despawn();
+        spawns.structures.push(StructureSpawn {
+            position: Vec3::new(transform.translation.x, transform.translation.y, 2.),
+            structure_key: "dead_plant",
+        });
+    }
+}
+
+const SPREAD_CHANCE_SCALE: u32 = 10_000;
+const NEIGHBOURS: [(f32, f32); 4] = [(0., 1.), (1., 0.), (0., -1.), (-1., 0.)];
+
+pub fn spread_crops_system(
+    query: Query<(&Crop, &Transform)>,
+    structure_query: Query<&Structure>,
+    world_query: Query<&World>,
+    mut spawns_query: Query<&mut Spawns>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if world_query.is_empty() || spawns_query.is_empty() {
+        return;
+    }
+
+    let world: &World = world_query.single();
+
+    if !world.tick_just_finished {
+        return;
+    }
+
+    let mut spawns: Mut<Spawns> = spawns_query.single_mut();
+    let rng = game_rng.stream(&game_config, RngStream::Crops);
+    let mut seeded = HashSet::new();
+
+    // Seeds already waiting to be spawned count towards the density too
+    let mut density: HashMap<String, usize> = HashMap::new();
+    for key in query
+        .iter()
+        .map(|(crop, _)| &crop.config.key)
+        .chain(spawns.crops.iter().map(|spawn| &spawn.config.key))
+    {
+        *density.entry(key.clone()).or_default() += 1;
+    }
+
+    for (crop, transform) in query.iter() {
+        let spread = match crop.config.spread {
+            Some(it) if crop.is_mature() && !crop.withered => it,
+            _ => continue,
+        };
 
-        if chance_to_grow > stage.chance_to_advance {
+        if rng.gen_range(0..SPREAD_CHANCE_SCALE) >= spread.chance {
             continue;
         }
 
-        match stages.stages.get(crop.current_stage_index + 1) {
-            Some(next_stage) => {
-                sprite.index = next_stage.sprite_index;
-                crop.current_stage_index += 1;
-            }
-            _ => {
-                if !spawns_query.is_empty() {
-                    let mut spawns = spawns_query.single_mut();
-                    spawns.crops.push(CropSpawn {
-                        config: crop.config.clone(),
-                        location: Vec2::new(transform.translation.x, transform.translation.y),
-                    });
-                }
-
-                commands.entity(entity).despawn();
-            }
+        let count = density.get(&crop.config.key).copied().unwrap_or(0);
+        if count >= spread.max_density as usize {
+            continue;
+        }
+
+        let mut neighbours = neighbouring_tiles(transform.translation.truncate(), &game_config);
+        neighbours.shuffle(rng);
+        let target = neighbours.into_iter().find(|(_, coordinate)| {
+            !seeded.contains(coordinate) && can_plant_at(coordinate, &occupancy, &structure_query)
+        });
+
+        if let Some((location, coordinate)) = target {
+            seeded.insert(coordinate);
+            *density.entry(crop.config.key.clone()).or_default() += 1;
+            spawns.crops.push(CropSpawn {
+                location,
+                config: crop.config.clone(),
+            });
         }
     }
 }
+
+pub fn neighbouring_tiles(
+    origin: Vec2,
+    game_config: &GameConfiguration,
+) -> Vec<(Vec2, Coordinate)> {
+    NEIGHBOURS
+        .iter()
+        .map(|(x, y)| {
+            let location = origin + Vec2::new(*x, *y) * game_config.tile_size();
+            let coordinate = grid_coordinate_from_world(
+                &location,
+                game_config.map_size(),
+                game_config.tile_size(),
+            );
+            (location, coordinate)
+        })
+        .collect()
+}
+
+// Seeds only go into tilled soil, and nothing else can be standing on it
+pub fn can_plant_at(
+    coordinate: &Coordinate,
+    occupancy: &TileOccupancy,
+    structure_query: &Query<&Structure>,
+) -> bool {
+    if !occupancy.crops_at(coordinate).is_empty() {
+        return false;
+    }
+
+    let structures: Vec<&Structure> = occupancy
+        .structures_at(coordinate)
+        .iter()
+        .filter_map(|entity| structure_query.get(*entity).ok())
+        .collect();
+
+    !structures.is_empty()
+        && structures
+            .iter()
+            .all(|structure| structure.is_tilled_soil())
+}
+
+type NewSoilQuery<'w, 's> =
+    Query<'w, 's, (Entity, &'static Structure), (Added<Structure>, Without<SoilMoisture>)>;
+
+// Freshly tilled soil starts out dry, restored soil already carries its saved moisture
+pub fn add_soil_moisture(mut commands: Commands, query: NewSoilQuery) {
+    for (entity, structure) in query.iter() {
+        if structure.is_tilled_soil() {
+            commands.entity(entity).insert(SoilMoisture::default());
+        }
+    }
+}
+
+pub fn drain_soil_moisture(
+    mut query: Query<(&mut SoilMoisture, &Transform)>,
+    world_query: Query<&World>,
+    ground_query: Query<(&GroundTile, &Transform)>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
+) {
+    if world_query.is_empty() {
+        return;
+    }
+
+    let world: &World = world_query.single();
+
+    if !world.tick_just_finished {
+        return;
+    }
+
+    for (mut moisture, transform) in query.iter_mut() {
+        let coordinate = grid_coordinate_from_world(
+            &transform.translation.truncate(),
+            game_config.map_size(),
+            game_config.tile_size(),
+        );
+        let soil = soil_at(&coordinate, &occupancy, &ground_query);
+        moisture.drain(1, soil.drainage);
+    }
+}
+
+fn soil_at(
+    coordinate: &Coordinate,
+    occupancy: &TileOccupancy,
+    ground_query: &Query<(&GroundTile, &Transform)>,
+) -> SoilConfig {
+    occupancy
+        .ground_on_top(coordinate, ground_query)
+        .map_or_else(SoilConfig::default, |ground| ground.soil)
+}
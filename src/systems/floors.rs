@@ -0,0 +1,177 @@
+use bevy::{
+    ecs::system::SystemParam,
+    math::Vec2,
+    prelude::{Commands, Entity, Mut, Query, Res, ResMut, Transform},
+};
+
+use crate::{
+    components::{
+        action::{CurrentAction, InteractAction},
+        body::Body,
+        crop::{Crop, CropStages},
+        floor::FloorLevel,
+        item::{Item, ItemType},
+        moisture::SoilMoisture,
+        player::Player,
+        spawns::{MapSpawn, Spawns},
+        structure::Structure,
+        world::World,
+    },
+    configuration::game::GameConfiguration,
+    save::{CropSave, CropStageSave, FloorSave, ItemSave, StructureSave, VisitedFloors},
+    systems::fov::FieldOfView,
+};
+
+#[derive(SystemParam)]
+pub struct FloorContents<'w, 's> {
+    world: Query<'w, 's, &'static World>,
+    field_of_view: Res<'w, FieldOfView>,
+    structures: Query<
+        'w,
+        's,
+        (
+            &'static Structure,
+            Option<&'static SoilMoisture>,
+            &'static Transform,
+            &'static FloorLevel,
+        ),
+    >,
+    crops: Query<
+        'w,
+        's,
+        (
+            &'static Crop,
+            &'static CropStages,
+            &'static Transform,
+            &'static FloorLevel,
+        ),
+    >,
+    items: Query<
+        'w,
+        's,
+        (
+            &'static Item,
+            &'static Body,
+            &'static Transform,
+            &'static FloorLevel,
+        ),
+    >,
+}
+
+impl<'w, 's> FloorContents<'w, 's> {
+    pub fn ticks(&self) -> u64 {
+        self.world.iter().next().map_or(0, |world| world.ticks)
+    }
+
+    pub fn capture(&self, level: usize, position: Vec2) -> FloorSave {
+        let structures = self
+            .structures
+            .iter()
+            .filter(|(_, _, _, floor)| floor.0 == level)
+            .map(|(structure, moisture, transform, _)| StructureSave {
+                key: structure.key().to_string(),
+                position: transform.translation,
+                current_health: structure.health.current_health,
+                moisture: moisture.map(|it| it.level),
+            })
+            .collect();
+
+        let crops = self
+            .crops
+            .iter()
+            .filter(|(_, _, _, floor)| floor.0 == level)
+            .map(|(crop, stages, transform, _)| CropSave {
+                key: crop.config.key.clone(),
+                position: Vec2::new(transform.translation.x, transform.translation.y),
+                current_stage_index: crop.current_stage_index,
+                growth: crop.growth,
+                neglected_ticks: crop.neglected_ticks,
+                withered: crop.withered,
+                withered_ticks: crop.withered_ticks,
+                stages: stages
+                    .stages
+                    .iter()
+                    .map(|stage| CropStageSave {
+                        ticks_in_stage: stage.ticks_in_stage,
+                        min_ticks_in_stage: stage.min_ticks_in_stage,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let items = self
+            .items
+            .iter()
+            .filter(|(_, _, _, floor)| floor.0 == level)
+            .map(|(item, body, transform, _)| ItemSave {
+                key: item.item_type.key().clone(),
+                position: transform.translation,
+                underground: body.underground,
+                quantity: match &item.item_type {
+                    ItemType::Stack(stack) => stack.quantity,
+                    _ => 1,
+                },
+            })
+            .collect();
+
+        FloorSave {
+            level,
+            position,
+            ticks: self.ticks(),
+            structures,
+            crops,
+            items,
+            explored: self.field_of_view.explored_on(level),
+        }
+    }
+}
+
+pub fn change_floor(
+    mut commands: Commands,
+    player_query: Query<(&Player, &CurrentAction, &Transform)>,
+    floor_contents: FloorContents,
+    floor_query: Query<(&FloorLevel, Entity)>,
+    mut visited_floors: ResMut<VisitedFloors>,
+    mut game_config: ResMut<GameConfiguration>,
+    mut spawns_query: Query<&mut Spawns>,
+) {
+    if player_query.is_empty() || spawns_query.is_empty() {
+        return;
+    }
+
+    let (_, action, transform): (&Player, &CurrentAction, &Transform) = player_query.single();
+
+    let level = game_config.level();
+    let target = match action.interact {
+        Some(InteractAction::DropFloors) => level + 1,
+        Some(InteractAction::ClimbFloors) if level > 0 => level - 1,
+        _ => return,
+    };
+
+    let position = Vec2::new(transform.translation.x, transform.translation.y);
+    visited_floors
+        .floors
+        .insert(level, floor_contents.capture(level, position));
+
+    for (floor, entity) in floor_query.iter() {
+        if floor.0 == level {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    game_config.change_level(target);
+    let map = game_config.generator().generate_top_down_map().unwrap();
+
+    let restore = visited_floors.floors.remove(&target);
+    let elapsed_ticks = restore.as_ref().map_or(0, |floor| {
+        floor_contents.ticks().saturating_sub(floor.ticks)
+    });
+
+    let mut spawns: Mut<Spawns> = spawns_query.single_mut();
+    spawns.map_spawn = Some(MapSpawn {
+        map,
+        restore,
+        player: None,
+        elapsed_ticks,
+    });
+}
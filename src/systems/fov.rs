@@ -0,0 +1,316 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    math::Vec2,
+    prelude::{
+        Added, Changed, Color, Query, RemovedComponents, Res, ResMut, Resource, Transform,
+        Visibility,
+    },
+    sprite::TextureAtlasSprite,
+};
+
+use crate::{
+    components::{
+        body::Body,
+        player::{Player, PlayerCoordinates},
+        structure::Structure,
+    },
+    configuration::{game::GameConfiguration, map::grid_coordinate_from_world},
+    save::FloorSave,
+};
+
+const REMEMBERED_TINT: Color = Color::rgb(0.35, 0.35, 0.35);
+
+// Each octant maps the (column, row) scan of `cast_light` onto the grid
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileSight {
+    Unseen,
+    Remembered,
+    Visible,
+}
+
+// Explored tiles are remembered per floor, for when the player comes back to it
+#[derive(Default, Resource)]
+pub struct FieldOfView {
+    level: Option<usize>,
+    origin: Option<(i32, i32)>,
+    pub visible: HashSet<(i32, i32)>,
+    pub explored: HashSet<(i32, i32)>,
+    other_floors: HashMap<usize, HashSet<(i32, i32)>>,
+}
+
+impl FieldOfView {
+    pub fn origin(&self) -> Option<(i32, i32)> {
+        self.origin
+    }
+
+    fn change_level(&mut self, level: usize) {
+        let explored = self.other_floors.remove(&level).unwrap_or_default();
+        let left = std::mem::replace(&mut self.explored, explored);
+        if let Some(previous) = self.level {
+            self.other_floors.insert(previous, left);
+        }
+
+        self.level = Some(level);
+        self.origin = None;
+        self.visible.clear();
+    }
+
+    // Row by row, which is how saves write them out
+    pub fn explored_on(&self, level: usize) -> Vec<(i32, i32)> {
+        let explored = if self.level == Some(level) {
+            &self.explored
+        } else {
+            match self.other_floors.get(&level) {
+                Some(it) => it,
+                _ => return Vec::new(),
+            }
+        };
+
+        let mut tiles: Vec<(i32, i32)> = explored.iter().copied().collect();
+        tiles.sort_by_key(|(x, y)| (*y, *x));
+        tiles
+    }
+
+    pub fn restore(&mut self, floor: &FloorSave, visited_floors: &[FloorSave]) {
+        self.level = Some(floor.level);
+        self.origin = None;
+        self.visible.clear();
+        self.explored = floor.explored.iter().copied().collect();
+        self.other_floors = visited_floors
+            .iter()
+            .map(|visited| (visited.level, visited.explored.iter().copied().collect()))
+            .collect();
+    }
+
+    pub fn sight(&self, tile: (i32, i32)) -> TileSight {
+        if self.visible.contains(&tile) {
+            TileSight::Visible
+        } else if self.explored.contains(&tile) {
+            TileSight::Remembered
+        } else {
+            TileSight::Unseen
+        }
+    }
+}
+
+pub fn grid_tile(position: Vec2, game_config: &GameConfiguration) -> (i32, i32) {
+    let coordinate =
+        grid_coordinate_from_world(&position, game_config.map_size(), game_config.tile_size());
+
+    (coordinate.x(), coordinate.y())
+}
+
+pub fn update_field_of_view(
+    player_query: Query<(&Player, &PlayerCoordinates)>,
+    structure_query: Query<(&Structure, &Transform)>,
+    changed_structures: Query<(), Changed<Structure>>,
+    removed_structures: RemovedComponents<Structure>,
+    mut field_of_view: ResMut<FieldOfView>,
+    game_config: Res<GameConfiguration>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (_, coordinates): (&Player, &PlayerCoordinates) = player_query.single();
+    let origin = match &coordinates.current {
+        Some(it) => (it.x(), it.y()),
+        _ => return,
+    };
+
+    if field_of_view.level != Some(game_config.level()) {
+        field_of_view.change_level(game_config.level());
+    }
+
+    let structures_changed =
+        !changed_structures.is_empty() || removed_structures.iter().next().is_some();
+    if field_of_view.origin == Some(origin) && !structures_changed {
+        return;
+    }
+
+    let opaque: HashSet<(i32, i32)> = structure_query
+        .iter()
+        .filter(|(structure, _)| structure.blocks_sight())
+        .map(|(_, transform)| grid_tile(transform.translation.truncate(), &game_config))
+        .collect();
+
+    let visible = shadowcast(origin, game_config.player_config.sight_radius, |tile| {
+        opaque.contains(&tile)
+    });
+
+    field_of_view.origin = Some(origin);
+    field_of_view.explored.extend(visible.iter().copied());
+    field_of_view.visible = visible;
+}
+
+pub fn apply_field_of_view(
+    field_of_view: Res<FieldOfView>,
+    added_bodies: Query<(), Added<Body>>,
+    mut query: Query<(&Body, &Transform, &mut Visibility, &mut TextureAtlasSprite)>,
+    game_config: Res<GameConfiguration>,
+) {
+    if !field_of_view.is_changed() && added_bodies.is_empty() {
+        return;
+    }
+
+    for (body, transform, mut visibility, mut sprite) in query.iter_mut() {
+        // Buried items stay hidden until they are dug up
+        if body.underground {
+            continue;
+        }
+
+        let tile = grid_tile(transform.translation.truncate(), &game_config);
+        match field_of_view.sight(tile) {
+            TileSight::Visible => {
+                visibility.is_visible = true;
+                sprite.color = Color::WHITE;
+            }
+            TileSight::Remembered => {
+                visibility.is_visible = true;
+                sprite.color = REMEMBERED_TINT;
+            }
+            TileSight::Unseen => visibility.is_visible = false,
+        }
+    }
+}
+
+// Recursive shadowcasting, walls are lit themselves but hide everything behind them
+pub fn shadowcast(
+    origin: (i32, i32),
+    radius: i32,
+    blocks_sight: impl Fn((i32, i32)) -> bool,
+) -> HashSet<(i32, i32)> {
+    let mut shadowcaster = Shadowcaster {
+        origin,
+        radius,
+        blocks_sight,
+        visible: HashSet::from([origin]),
+    };
+
+    for octant in OCTANTS {
+        shadowcaster.cast_light(1, 1.0, 0.0, octant);
+    }
+
+    shadowcaster.visible
+}
+
+struct Shadowcaster<F: Fn((i32, i32)) -> bool> {
+    origin: (i32, i32),
+    radius: i32,
+    blocks_sight: F,
+    visible: HashSet<(i32, i32)>,
+}
+
+impl<F: Fn((i32, i32)) -> bool> Shadowcaster<F> {
+    fn cast_light(&mut self, row: i32, mut start: f32, end: f32, octant: (i32, i32, i32, i32)) {
+        if start < end {
+            return;
+        }
+
+        let (xx, xy, yx, yy) = octant;
+        let mut new_start = 0.0;
+
+        for distance in row..=self.radius {
+            let mut blocked = false;
+            let dy = -distance;
+
+            for dx in -distance..=0 {
+                let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+                let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+
+                if start < right_slope {
+                    continue;
+                }
+                if end > left_slope {
+                    break;
+                }
+
+                let tile = (
+                    self.origin.0 + dx * xx + dy * xy,
+                    self.origin.1 + dx * yx + dy * yy,
+                );
+                if dx * dx + dy * dy <= self.radius * self.radius {
+                    self.visible.insert(tile);
+                }
+
+                let opaque = (self.blocks_sight)(tile);
+                if blocked {
+                    if opaque {
+                        new_start = right_slope;
+                        continue;
+                    }
+                    blocked = false;
+                    start = new_start;
+                } else if opaque && distance < self.radius {
+                    blocked = true;
+                    self.cast_light(distance + 1, start, left_slope, octant);
+                    new_start = right_slope;
+                }
+            }
+
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::shadowcast;
+
+    #[test]
+    fn open_ground_is_visible_out_to_the_radius() {
+        let visible = shadowcast((0, 0), 2, |_| false);
+
+        let expected: HashSet<(i32, i32)> = (-2..=2)
+            .flat_map(|x| (-2..=2).map(move |y| (x, y)))
+            .filter(|(x, y)| x * x + y * y <= 4)
+            .collect();
+        assert_eq!(visible, expected);
+    }
+
+    #[test]
+    fn walls_hide_the_tiles_behind_them() {
+        let visible = shadowcast((0, 0), 5, |tile| tile == (2, 0));
+
+        assert!(visible.contains(&(1, 0)));
+        assert!(!visible.contains(&(3, 0)));
+        assert!(!visible.contains(&(4, 0)));
+        assert!(visible.contains(&(-3, 0)));
+    }
+
+    #[test]
+    fn walls_are_lit_themselves() {
+        let visible = shadowcast((0, 0), 5, |(x, _)| x == 2);
+
+        assert!(visible.contains(&(2, 0)));
+        assert!(visible.contains(&(2, 1)));
+        assert!(visible.contains(&(2, -1)));
+        assert!(!visible.iter().any(|(x, _)| *x > 2));
+    }
+
+    #[test]
+    fn nothing_past_the_radius_is_visible() {
+        let visible = shadowcast((10, 10), 3, |_| false);
+
+        assert!(visible.contains(&(13, 10)));
+        assert!(visible.contains(&(12, 12)));
+        assert!(!visible.contains(&(14, 10)));
+        assert!(!visible.contains(&(13, 11)));
+    }
+}
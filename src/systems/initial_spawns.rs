@@ -11,6 +11,9 @@ use crate::{
         text::{PlayerStatsText, PlayerStatsTextBundle},
     },
     configuration::game::GameConfiguration,
+    rng::GameRng,
+    save::{LoadedSave, VisitedFloors},
+    systems::fov::FieldOfView,
 };
 
 pub fn spawn_player_text(
@@ -32,16 +35,40 @@ pub fn spawn_opening_bundles(
     mut commands: Commands,
     query: Query<&Player>,
     mut game_config: ResMut<GameConfiguration>,
+    mut loaded_save: ResMut<LoadedSave>,
+    mut game_rng: ResMut<GameRng>,
+    mut visited_floors: ResMut<VisitedFloors>,
+    mut field_of_view: ResMut<FieldOfView>,
 ) {
     if !query.is_empty() {
         return;
     }
 
-    let generator = game_config.generator(false);
+    let (restore, player) = match loaded_save.0.take() {
+        Some(save) => {
+            game_config.restore_level(save.seed, save.level);
+            game_rng.restore(&save.rng);
+            field_of_view.restore(&save.floor, &save.visited_floors);
+            visited_floors.floors = save
+                .visited_floors
+                .into_iter()
+                .map(|floor| (floor.level, floor))
+                .collect();
+            (Some(save.floor), Some(save.player))
+        }
+        _ => (None, None),
+    };
+
+    let generator = game_config.generator();
     let map = generator.generate_top_down_map().unwrap();
 
     let spawns = Spawns {
-        map_spawn: Some(MapSpawn { map }),
+        map_spawn: Some(MapSpawn {
+            map,
+            restore,
+            player,
+            elapsed_ticks: 0,
+        }),
         ..Default::default()
     };
     commands.spawn(spawns);
@@ -1,31 +1,38 @@
 use bevy::{
     input::Input,
     math::{Vec2, Vec3},
-    prelude::{Entity, KeyCode, Mut, Query, Res, ResMut, Resource, Transform, Visibility},
+    prelude::{FromWorld, Mut, Query, Res, ResMut, Resource, Transform, Visibility, World},
     render::camera::Camera,
     time::{Time, Timer},
 };
 
 use crate::{
     components::{
-        action::{ClearAction, CurrentAction, DigAction, InteractAction, PlantCropAction},
-        body::Body,
-        bounding_box::BoundingBox,
+        action::{
+            ClearAction, CurrentAction, DigAction, HarvestAction, InteractAction, PlantCropAction,
+            WaterAction,
+        },
         cameras::{GameCamera, GameCameraState},
+        crop::Crop,
         movement::Direction,
         player::{Player, PlayerInventory, PlayerMovement},
         structure::Structure,
         text::PlayerStatsText,
     },
-    configuration::{game::GameConfiguration, timers::movement_timer},
+    configuration::{
+        controls::InputAction, game::GameConfiguration, map::grid_coordinate_from_world,
+        timers::movement_timer,
+    },
+    systems::occupancy::TileOccupancy,
 };
 
 #[derive(Resource)]
 pub struct MovementInputTimer(pub Timer);
 
-impl Default for MovementInputTimer {
-    fn default() -> Self {
-        Self(movement_timer())
+impl FromWorld for MovementInputTimer {
+    fn from_world(world: &mut World) -> Self {
+        let game_config = world.resource::<GameConfiguration>();
+        Self(movement_timer(game_config.controls_config.repeat_delay))
     }
 }
 
@@ -40,7 +47,7 @@ fn y_axis_speed(tile_size: f32) -> Vec2 {
 pub fn movement_input_system(
     time: Res<Time>,
     mut timer: ResMut<MovementInputTimer>,
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     mut query: Query<(&Player, &mut PlayerMovement)>,
     game_config: Res<GameConfiguration>,
 ) {
@@ -53,22 +60,22 @@ pub fn movement_input_system(
     movement.speed.current = Vec2::ZERO;
     movement.direction = Direction::None;
 
-    if keyboard_input.just_pressed(KeyCode::Left) {
+    if actions.just_pressed(InputAction::MoveWest) {
         movement.speed.current -= x_axis_speed(game_config.tile_size());
         movement.direction = movement.direction + Direction::West;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Right) {
+    if actions.just_pressed(InputAction::MoveEast) {
         movement.speed.current += x_axis_speed(game_config.tile_size());
         movement.direction = movement.direction + Direction::East;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Up) {
+    if actions.just_pressed(InputAction::MoveNorth) {
         movement.speed.current += y_axis_speed(game_config.tile_size());
         movement.direction = movement.direction + Direction::North;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Down) {
+    if actions.just_pressed(InputAction::MoveSouth) {
         movement.speed.current -= y_axis_speed(game_config.tile_size());
         movement.direction = movement.direction + Direction::South;
     }
@@ -79,22 +86,22 @@ pub fn movement_input_system(
     }
 
     if timer.0.tick(time.delta()).just_finished() {
-        if keyboard_input.pressed(KeyCode::Left) {
+        if actions.pressed(InputAction::MoveWest) {
             movement.speed.current -= x_axis_speed(game_config.tile_size());
             movement.direction = movement.direction + Direction::West;
         }
 
-        if keyboard_input.pressed(KeyCode::Right) {
+        if actions.pressed(InputAction::MoveEast) {
             movement.speed.current += x_axis_speed(game_config.tile_size());
             movement.direction = movement.direction + Direction::East;
         }
 
-        if keyboard_input.pressed(KeyCode::Up) {
+        if actions.pressed(InputAction::MoveNorth) {
             movement.speed.current += y_axis_speed(game_config.tile_size());
             movement.direction = movement.direction + Direction::North;
         }
 
-        if keyboard_input.pressed(KeyCode::Down) {
+        if actions.pressed(InputAction::MoveSouth) {
             movement.speed.current -= y_axis_speed(game_config.tile_size());
             movement.direction = movement.direction + Direction::South;
         }
@@ -102,9 +109,12 @@ pub fn movement_input_system(
 }
 
 pub fn action_input_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     mut query: Query<(&Player, &mut CurrentAction, &Transform, &PlayerInventory)>,
-    structure_query: Query<(&Structure, &Body, Entity)>,
+    structure_query: Query<&Structure>,
+    crop_query: Query<&Crop>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
 ) {
     if query.is_empty() {
         return;
@@ -117,16 +127,20 @@ pub fn action_input_system(
         &PlayerInventory,
     ) = query.single_mut();
 
-    if keyboard_input.just_pressed(KeyCode::E) {
+    if actions.just_pressed(InputAction::Interact) {
         let x = transform.translation.x;
         let y = transform.translation.y;
-        let bounding_box = BoundingBox::square(x, y, 60.0);
-
-        for structure_data in structure_query.iter() {
-            let (structure, body, entity): (&Structure, &Body, Entity) = structure_data;
-            if !body.intersects_box(&bounding_box) {
-                continue;
-            }
+        let coordinate = grid_coordinate_from_world(
+            &Vec2::new(x, y),
+            game_config.map_size(),
+            game_config.tile_size(),
+        );
+
+        for entity in occupancy.structures_at(&coordinate) {
+            let structure: &Structure = match structure_query.get(*entity) {
+                Ok(it) => it,
+                _ => continue,
+            };
 
             // Can't dig while in a structure
             if inventory.shovel_equipped() {
@@ -139,8 +153,33 @@ pub fn action_input_system(
                 return;
             }
 
+            if structure.is_ladder() {
+                action.interact = Some(InteractAction::ClimbFloors);
+                return;
+            }
+
             if structure.can_be_cleared() && inventory.clearing_item_equipped() {
-                action.interact = Some(InteractAction::ClearAction(ClearAction { entity }));
+                action.interact =
+                    Some(InteractAction::ClearAction(ClearAction { entity: *entity }));
+                return;
+            }
+
+            if structure.is_tilled_soil() && inventory.watering_can_equipped() {
+                action.interact =
+                    Some(InteractAction::WaterAction(WaterAction { entity: *entity }));
+                return;
+            }
+        }
+
+        for entity in occupancy.crops_at(&coordinate) {
+            let crop: &Crop = match crop_query.get(*entity) {
+                Ok(it) => it,
+                _ => continue,
+            };
+
+            // Withered crops are pulled up like a harvest, they just don't yield anything
+            if crop.is_mature() || crop.withered {
+                action.interact = Some(InteractAction::Harvest(HarvestAction { entity: *entity }));
                 return;
             }
         }
@@ -170,10 +209,10 @@ pub fn reset_action_input_system(mut query: Query<(&Player, &mut CurrentAction)>
 }
 
 pub fn toggle_coordinates_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     mut query: Query<(&PlayerStatsText, &mut Visibility)>,
 ) {
-    if !keyboard_input.just_pressed(KeyCode::Slash) {
+    if !actions.just_pressed(InputAction::ToggleCoordinates) {
         return;
     }
 
@@ -187,11 +226,11 @@ pub fn toggle_coordinates_system(
 }
 
 pub fn zoom_camera_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     mut query: Query<(&GameCamera, &Camera, &mut Transform)>,
     mut camera_state_query: Query<&mut GameCameraState>,
 ) {
-    if !keyboard_input.just_pressed(KeyCode::Z) {
+    if !actions.just_pressed(InputAction::Zoom) {
         return;
     }
 
@@ -1,19 +1,19 @@
 use bevy::{
     input::Input,
-    prelude::{KeyCode, Res, ResMut, State},
+    prelude::{Res, ResMut, State},
 };
 
-use crate::states::AppState;
+use crate::{configuration::controls::InputAction, states::AppState};
 
 pub fn open_close_inventory_input_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     mut state: ResMut<State<AppState>>,
 ) {
     if state.current().ne(&AppState::InGame) && state.current().ne(&AppState::InventoryScreen) {
         return;
     }
 
-    if keyboard_input.just_pressed(KeyCode::I) {
+    if actions.just_pressed(InputAction::OpenInventory) {
         if state.current().eq(&AppState::InGame) {
             state.set(AppState::InventoryScreen).unwrap();
         } else {
@@ -5,6 +5,7 @@ use bevy::{
 
 use crate::{
     components::{inventory::CurrentInventorySelection, player::PlayerInventory},
+    configuration::controls::InputAction,
     states::AppState,
 };
 
@@ -18,6 +19,7 @@ pub fn add_current_selection(mut commands: Commands, query: Query<&CurrentInvent
 
 pub fn selection_input(
     keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Input<InputAction>>,
     state: ResMut<State<AppState>>,
     mut query: Query<&mut CurrentInventorySelection>,
     current_inventory_query: Query<&PlayerInventory>,
@@ -29,7 +31,7 @@ pub fn selection_input(
     let mut current_selection: Mut<'_, CurrentInventorySelection> = query.single_mut();
     let current_inventory: &PlayerInventory = current_inventory_query.single();
 
-    if keyboard_input.just_pressed(KeyCode::Down) {
+    if actions.just_pressed(InputAction::SelectNext) {
         let new_index = match current_inventory.current_selected_index {
             Some(it) => {
                 if it + 1 > current_selection.max_index {
@@ -44,7 +46,7 @@ pub fn selection_input(
         return;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Up) {
+    if actions.just_pressed(InputAction::SelectPrevious) {
         let new_index = match current_inventory.current_selected_index {
             Some(it) => {
                 if it == 0 {
@@ -89,24 +91,24 @@ pub fn select_item(
 
     if let Some(index) = selection.index {
         inventory.current_selected_index = Some(index);
-        if index > inventory.held_seeds.len() - 1 {
+        if index >= inventory.held_seeds.len() {
             let tool_index = index - inventory.held_seeds.len();
             if let Some(tool_config) = inventory.held_tools.get(tool_index) {
                 inventory.current_tool = Some(tool_config.to_tool());
                 inventory.current_crop_config = None;
             }
-        } else if let Some(seed_config) = inventory.held_seeds.get(index) {
-            inventory.current_crop_config = Some(seed_config.clone());
+        } else if let Some(seeds) = inventory.held_seeds.get(index) {
+            inventory.current_crop_config = Some(seeds.config.clone());
             inventory.current_tool = None;
         }
     } else if let Some(key_code) = selection.key_code {
-        if let Some((i, crop_config)) = inventory
+        if let Some((i, seeds)) = inventory
             .held_seeds
             .iter()
             .enumerate()
-            .find(|(_, config)| config.inventory_selector.key_code == key_code)
+            .find(|(_, seeds)| seeds.config.inventory_selector.key_code == key_code)
         {
-            inventory.current_crop_config = Some(crop_config.clone());
+            inventory.current_crop_config = Some(seeds.config.clone());
             inventory.current_selected_index = Some(i);
             inventory.current_tool = None;
         } else if let Some((i, tool_config)) = inventory
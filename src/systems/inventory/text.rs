@@ -35,7 +35,8 @@ pub fn add_text(
 
     let mut seed_count = 0;
     let mut total_count = 0;
-    for (index, crop_config) in player_inventory.held_seeds.iter().enumerate() {
+    for (index, seeds) in player_inventory.held_seeds.iter().enumerate() {
+        let crop_config = &seeds.config;
         if crop_config.stages.is_empty() {
             continue;
         }
@@ -50,8 +51,8 @@ pub fn add_text(
             top,
             PADDING,
             format!(
-                "{}   {}",
-                crop_config.inventory_selector.display_code, crop_config.name
+                "{}   {} x{}",
+                crop_config.inventory_selector.display_code, crop_config.name, seeds.quantity
             ),
             &font,
             FONT_SIZE,
@@ -83,8 +84,24 @@ pub fn add_text(
         total_count += 1;
     }
 
+    let held_lines = player_inventory
+        .held_produce
+        .iter()
+        .map(|produce| format!("{} x{}", produce.name, produce.quantity))
+        .chain(
+            player_inventory
+                .held_items
+                .iter()
+                .map(|stack| format!("{} x{}", stack.config.name(), stack.quantity)),
+        );
+    for (index, line) in held_lines.enumerate() {
+        let top = PADDING + (INVENTORY_ITEM_SIZE * ((index + total_count) as f32 + 1.0));
+        let text_bundle = InventoryTextBundle::build(None, top, PADDING, line, &font, FONT_SIZE);
+        commands.spawn(text_bundle);
+    }
+
     let mut current_selection: Mut<'_, CurrentInventorySelection> = selection_query.single_mut();
-    current_selection.max_index = total_count - 1;
+    current_selection.max_index = total_count.saturating_sub(1);
 }
 
 pub fn update_text_colour(
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::Input,
+    prelude::{
+        Added, AnyOf, Assets, Changed, Commands, Handle, Image, ImageBundle, Or, Query,
+        RemovedComponents, Res, ResMut, Resource, Transform, Visibility, With, Without,
+    },
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+    ui::{PositionType, Size, Style, UiImage, UiRect, Val},
+    window::Windows,
+};
+use tdlg::map::TopDownMap;
+
+use crate::{
+    components::{
+        body::Body,
+        crop::Crop,
+        item::Item,
+        map_view::{FullMap, Minimap},
+        structure::Structure,
+    },
+    configuration::{controls::InputAction, game::GameConfiguration, layers::MapLayer},
+    systems::fov::{grid_tile, FieldOfView, TileSight},
+};
+
+const MINIMAP_SIZE: f32 = 180.0;
+const MINIMAP_MARGIN: f32 = 10.0;
+const FULL_MAP_SCREEN_SHARE: f32 = 0.9;
+
+const UNSEEN_COLOUR: [u8; 4] = [0, 0, 0, 160];
+const VISIBLE_FLOOR_COLOUR: [u8; 4] = [170, 170, 170, 255];
+const REMEMBERED_FLOOR_COLOUR: [u8; 4] = [95, 95, 95, 255];
+const VISIBLE_WALL_COLOUR: [u8; 4] = [90, 70, 50, 255];
+const REMEMBERED_WALL_COLOUR: [u8; 4] = [55, 45, 35, 255];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapTerrain {
+    Floor,
+    Wall,
+}
+
+// The terrain of the current floor, kept from the TopDownMap it was spawned from
+#[derive(Default, Resource)]
+pub struct FloorLayout {
+    pub size: u16,
+    pub tiles: HashMap<(i32, i32), MapTerrain>,
+}
+
+impl FloorLayout {
+    pub fn from_map(map: &TopDownMap, size: u16) -> Self {
+        let mut tiles = HashMap::new();
+
+        for cell in map.grid().cells() {
+            let layers = cell
+                .layers()
+                .iter()
+                .filter_map(MapLayer::from_layer_type)
+                .collect::<Vec<MapLayer>>();
+
+            let terrain = if layers
+                .iter()
+                .any(|layer| matches!(layer, MapLayer::RoomWall | MapLayer::OuterWall))
+            {
+                MapTerrain::Wall
+            } else if layers.is_empty() {
+                continue;
+            } else {
+                MapTerrain::Floor
+            };
+
+            let coordinate = cell.coordinate();
+            tiles.insert((coordinate.x(), coordinate.y()), terrain);
+        }
+
+        Self { size, tiles }
+    }
+}
+
+// Later markers are drawn over earlier ones
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MapMarker {
+    Item,
+    Crop,
+    Exit,
+    Player,
+}
+
+impl MapMarker {
+    fn colour(&self) -> [u8; 4] {
+        match self {
+            Self::Item => [80, 160, 255, 255],
+            Self::Crop => [60, 200, 60, 255],
+            Self::Exit => [210, 60, 210, 255],
+            Self::Player => [255, 220, 0, 255],
+        }
+    }
+}
+
+#[derive(Default, Resource)]
+pub struct MapView {
+    pub full_map_open: bool,
+    image: Option<Handle<Image>>,
+    markers: Vec<((i32, i32), MapMarker)>,
+    redraw: bool,
+}
+
+type MarkerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        Option<&'static Body>,
+        AnyOf<(&'static Crop, &'static Item, &'static Structure)>,
+    ),
+>;
+
+type MarkerChangeFilter = (
+    Or<(
+        Added<Crop>,
+        Added<Item>,
+        Added<Structure>,
+        Changed<Body>,
+        Changed<Transform>,
+    )>,
+    Or<(With<Crop>, With<Item>, With<Structure>)>,
+);
+
+// Markers only move when crops, items or structures come, go or change
+#[derive(SystemParam)]
+pub struct MarkerChanges<'w, 's> {
+    changed: Query<'w, 's, (), MarkerChangeFilter>,
+    removed_crops: RemovedComponents<'w, Crop>,
+    removed_items: RemovedComponents<'w, Item>,
+    removed_structures: RemovedComponents<'w, Structure>,
+}
+
+impl<'w, 's> MarkerChanges<'w, 's> {
+    fn any(&self) -> bool {
+        !self.changed.is_empty()
+            || self.removed_crops.iter().next().is_some()
+            || self.removed_items.iter().next().is_some()
+            || self.removed_structures.iter().next().is_some()
+    }
+}
+
+pub fn spawn_map_views(
+    mut commands: Commands,
+    query: Query<(), With<Minimap>>,
+    mut map_view: ResMut<MapView>,
+    mut images: ResMut<Assets<Image>>,
+    windows: Res<Windows>,
+) {
+    if !query.is_empty() {
+        return;
+    }
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &UNSEEN_COLOUR,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+    let handle = images.add(image);
+    map_view.image = Some(handle.clone());
+    map_view.markers.clear();
+    map_view.redraw = true;
+
+    commands
+        .spawn(ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(MINIMAP_MARGIN),
+                    right: Val::Px(MINIMAP_MARGIN),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Px(MINIMAP_SIZE), Val::Px(MINIMAP_SIZE)),
+                ..Default::default()
+            },
+            image: UiImage(handle.clone()),
+            ..Default::default()
+        })
+        .insert(Minimap);
+
+    let window = windows.get_primary().unwrap();
+    let full_size = window.width().min(window.height()) * FULL_MAP_SCREEN_SHARE;
+
+    commands
+        .spawn(ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px((window.height() - full_size) / 2.0),
+                    left: Val::Px((window.width() - full_size) / 2.0),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Px(full_size), Val::Px(full_size)),
+                ..Default::default()
+            },
+            image: UiImage(handle),
+            visibility: Visibility { is_visible: false },
+            ..Default::default()
+        })
+        .insert(FullMap);
+}
+
+pub fn toggle_map_system(actions: Res<Input<InputAction>>, mut map_view: ResMut<MapView>) {
+    if actions.just_pressed(InputAction::ToggleMap) {
+        map_view.full_map_open = !map_view.full_map_open;
+    }
+}
+
+pub fn show_map_views(
+    map_view: Res<MapView>,
+    mut minimap_query: Query<&mut Visibility, (With<Minimap>, Without<FullMap>)>,
+    mut full_map_query: Query<&mut Visibility, (With<FullMap>, Without<Minimap>)>,
+) {
+    for mut visibility in minimap_query.iter_mut() {
+        visibility.is_visible = !map_view.full_map_open;
+    }
+
+    for mut visibility in full_map_query.iter_mut() {
+        visibility.is_visible = map_view.full_map_open;
+    }
+}
+
+type MapViewFilter = Or<(With<Minimap>, With<FullMap>)>;
+
+pub fn hide_map_views(mut query: Query<&mut Visibility, MapViewFilter>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
+
+pub fn update_map_image(
+    layout: Res<FloorLayout>,
+    field_of_view: Res<FieldOfView>,
+    marker_query: MarkerQuery,
+    marker_changes: MarkerChanges,
+    mut map_view: ResMut<MapView>,
+    mut images: ResMut<Assets<Image>>,
+    game_config: Res<GameConfiguration>,
+) {
+    // Nothing to draw until spawn_map has laid out the floor
+    if layout.size == 0 {
+        return;
+    }
+
+    if !map_view.redraw
+        && !layout.is_changed()
+        && !field_of_view.is_changed()
+        && !marker_changes.any()
+    {
+        return;
+    }
+
+    let mut markers = Vec::new();
+    if let Some(origin) = field_of_view.origin() {
+        markers.push((origin, MapMarker::Player));
+    }
+
+    for (transform, body, (crop, item, structure)) in marker_query.iter() {
+        let marker = match (crop, item, structure) {
+            (Some(_), _, _) => MapMarker::Crop,
+            (_, Some(_), _) if !body.is_some_and(|body| body.underground) => MapMarker::Item,
+            (_, _, Some(structure)) if structure.is_exit() => MapMarker::Exit,
+            _ => continue,
+        };
+
+        // Only what the player has seen at some point goes on the map
+        let tile = grid_tile(transform.translation.truncate(), &game_config);
+        if field_of_view.sight(tile) != TileSight::Unseen {
+            markers.push((tile, marker));
+        }
+    }
+    markers.sort_by_key(|(_, marker)| *marker);
+
+    if !map_view.redraw
+        && !layout.is_changed()
+        && !field_of_view.is_changed()
+        && markers == map_view.markers
+    {
+        return;
+    }
+
+    let image = match map_view.image.as_ref().and_then(|it| images.get_mut(it)) {
+        Some(it) => it,
+        _ => return,
+    };
+
+    let size = layout.size as u32;
+    if image.texture_descriptor.size.width != size {
+        image.resize(Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        });
+    }
+
+    draw_floor_map(&layout, &field_of_view, &markers, &mut image.data);
+    map_view.markers = markers;
+    map_view.redraw = false;
+}
+
+fn draw_floor_map(
+    layout: &FloorLayout,
+    field_of_view: &FieldOfView,
+    markers: &[((i32, i32), MapMarker)],
+    data: &mut [u8],
+) {
+    let size = layout.size as i32;
+    let mut paint = |(x, y): (i32, i32), colour: [u8; 4]| {
+        if x < 0 || y < 0 || x >= size || y >= size {
+            return;
+        }
+
+        // Image rows run top to bottom, the grid runs south to north
+        let index = (((size - 1 - y) * size + x) * 4) as usize;
+        data[index..index + 4].copy_from_slice(&colour);
+    };
+
+    for y in 0..size {
+        for x in 0..size {
+            let colour = match (layout.tiles.get(&(x, y)), field_of_view.sight((x, y))) {
+                (_, TileSight::Unseen) | (None, _) => UNSEEN_COLOUR,
+                (Some(MapTerrain::Floor), TileSight::Visible) => VISIBLE_FLOOR_COLOUR,
+                (Some(MapTerrain::Floor), TileSight::Remembered) => REMEMBERED_FLOOR_COLOUR,
+                (Some(MapTerrain::Wall), TileSight::Visible) => VISIBLE_WALL_COLOUR,
+                (Some(MapTerrain::Wall), TileSight::Remembered) => REMEMBERED_WALL_COLOUR,
+            };
+            paint((x, y), colour);
+        }
+    }
+
+    for (tile, marker) in markers {
+        paint(*tile, marker.colour());
+    }
+}
@@ -1,6 +1,6 @@
 use bevy::{
     math::Vec2,
-    prelude::{Entity, Mut, Query, Res, Transform, Visibility, Without},
+    prelude::{Mut, Query, Res, ResMut, Transform, Visibility, Without},
     render::camera::Camera,
     text::Text,
 };
@@ -9,15 +9,15 @@ use crate::{
     components::{
         action::{CurrentAction, PickupAction},
         body::Body,
-        bounding_box::BoundingBox,
         cameras::GameCamera,
         item::Item,
-        movement::Direction,
         player::{Player, PlayerCoordinates, PlayerInventory, PlayerMovement},
         structure::Structure,
         text::PlayerStatsText,
     },
     configuration::{game::GameConfiguration, map::grid_coordinate_from_world},
+    rng::{GameRng, RngStream},
+    systems::occupancy::TileOccupancy,
 };
 
 type PlayerMovementQuery = (
@@ -30,7 +30,9 @@ type PlayerMovementQuery = (
 
 pub fn check_item_pickup(
     mut query: Query<(&Player, &Transform, &mut CurrentAction)>,
-    item_query: Query<(&Item, &Body, &Visibility, Entity)>,
+    item_query: Query<(&Item, &Body, &Visibility)>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
 ) {
     if query.is_empty() {
         return;
@@ -39,24 +41,31 @@ pub fn check_item_pickup(
     let (_, transform, mut current_action): (&Player, &Transform, Mut<CurrentAction>) =
         query.single_mut();
 
-    let x = transform.translation.x;
-    let y = transform.translation.y;
-
-    let bounding_box = BoundingBox::square(x, y, 60.0);
+    let coordinate = grid_coordinate_from_world(
+        &transform.translation.truncate(),
+        game_config.map_size(),
+        game_config.tile_size(),
+    );
 
-    for item_data in item_query.iter() {
-        let (_, body, visible, entity): (&Item, &Body, &Visibility, Entity) = item_data;
+    for entity in occupancy.items_at(&coordinate) {
+        let (_, body, visible): (&Item, &Body, &Visibility) = match item_query.get(*entity) {
+            Ok(it) => it,
+            _ => continue,
+        };
 
-        if body.intersects_box(&bounding_box) && visible.is_visible && !body.underground {
+        if visible.is_visible && !body.underground {
             println!("picking up item");
-            current_action.pickup = Some(PickupAction { target: entity })
+            current_action.pickup = Some(PickupAction { target: *entity })
         }
     }
 }
 
 pub fn player_movement(
     mut query: Query<PlayerMovementQuery>,
-    cell_query: Query<(&Structure, &Body, Entity)>,
+    structure_query: Query<&Structure>,
+    occupancy: Res<TileOccupancy>,
+    game_config: Res<GameConfiguration>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     if query.is_empty() {
         return;
@@ -73,36 +82,41 @@ pub fn player_movement(
     let x = movement.speed.current.x + transform.translation.x;
     let y = movement.speed.current.y + transform.translation.y;
 
-    let bounding_box = BoundingBox::square(x, y, 60.0);
+    let coordinate = grid_coordinate_from_world(
+        &Vec2::new(x, y),
+        game_config.map_size(),
+        game_config.tile_size(),
+    );
 
     let mut player_would_hit_wall: bool = false;
 
-    for cell_data in cell_query.iter() {
-        let (wall, body, entity): (&Structure, &Body, Entity) = cell_data;
+    for entity in occupancy.structures_at(&coordinate) {
+        let wall: &Structure = match structure_query.get(*entity) {
+            Ok(it) => it,
+            _ => continue,
+        };
 
-        if body.intersects_box(&bounding_box) {
-            if wall.can_be_walked_on() {
-                continue;
-            }
+        if wall.can_be_walked_on() {
+            continue;
+        }
 
-            if !wall.can_be_broken() && !wall.can_be_walked_on() {
-                player_would_hit_wall = true;
-                break;
-            }
+        if !wall.can_be_broken() && !wall.can_be_walked_on() {
+            player_would_hit_wall = true;
+            break;
+        }
 
-            if inventory.current_tool.is_none() {
-                player_would_hit_wall = true;
-                break;
-            }
+        if inventory.current_tool.is_none() {
+            player_would_hit_wall = true;
+            break;
+        }
 
-            let tool = inventory.current_tool.clone().unwrap();
+        let tool = inventory.current_tool.clone().unwrap();
 
-            if let Some(damage) = tool.damage {
-                let damage_done = damage.damage_dealt();
-                action.hit_entity(damage_done, entity);
-                player_would_hit_wall = true;
-                break;
-            }
+        if let Some(damage) = tool.damage {
+            let damage_done = damage.damage_dealt(game_rng.stream(&game_config, RngStream::Combat));
+            action.hit_entity(damage_done, *entity);
+            player_would_hit_wall = true;
+            break;
         }
     }
 
@@ -170,73 +184,3 @@ pub fn camera_movement(
     camera_transform.translation.x = player_x;
     camera_transform.translation.y = player_y;
 }
-
-pub fn check_floor_collision(
-    player_query: Query<(&Player, &Transform, &PlayerMovement)>,
-    mut ground_cell_query: Query<(&Body, &mut Visibility)>,
-    game_config: Res<GameConfiguration>,
-) {
-    if player_query.is_empty() {
-        return;
-    }
-
-    let (_, transform, movement): (&Player, &Transform, &PlayerMovement) = player_query.single();
-
-    let bounding_boxes = build_visibility_box(
-        transform.translation.x,
-        transform.translation.y,
-        &movement.direction,
-        game_config.tile_size(),
-    );
-
-    for cell_data in ground_cell_query.iter_mut() {
-        let (grid_cell, mut visible): (&Body, Mut<Visibility>) = cell_data;
-
-        if bounding_boxes
-            .iter()
-            .any(|bounds| grid_cell.intersects_box(bounds))
-            && !visible.is_visible
-            && !grid_cell.underground
-        {
-            visible.is_visible = true;
-        }
-    }
-}
-
-fn build_visibility_box(x: f32, y: f32, direction: &Direction, tile_size: f32) -> Vec<BoundingBox> {
-    let width = tile_size - 2.0;
-
-    let player_box = BoundingBox::square(x, y, width);
-
-    let mut visibility_boxes: Vec<BoundingBox> = match direction {
-        Direction::North => vec![BoundingBox::square(x, y + tile_size, width)],
-        Direction::South => vec![BoundingBox::square(x, y - tile_size, width)],
-        Direction::East => vec![BoundingBox::square(x + tile_size, y, width)],
-        Direction::West => vec![BoundingBox::square(x - tile_size, y, width)],
-        Direction::NorthEast => vec![
-            BoundingBox::square(x, y + tile_size, width),
-            BoundingBox::square(x + tile_size, y, width),
-            BoundingBox::square(x + tile_size, y + tile_size, width),
-        ],
-        Direction::NorthWest => vec![
-            BoundingBox::square(x, y + tile_size, width),
-            BoundingBox::square(x - tile_size, y, width),
-            BoundingBox::square(x - tile_size, y + tile_size, width),
-        ],
-        Direction::SouthEast => vec![
-            BoundingBox::square(x, y - tile_size, width),
-            BoundingBox::square(x + tile_size, y, width),
-            BoundingBox::square(x + tile_size, y - tile_size, width),
-        ],
-        Direction::SouthWest => vec![
-            BoundingBox::square(x, y - tile_size, width),
-            BoundingBox::square(x - tile_size, y, width),
-            BoundingBox::square(x - tile_size, y - tile_size, width),
-        ],
-        _ => Vec::new(),
-    };
-
-    visibility_boxes.push(player_box);
-
-    visibility_boxes
-}
@@ -0,0 +1,134 @@
+use bevy::{
+    input::Input,
+    prelude::{AssetServer, Commands, Entity, Handle, Mut, Query, Res, ResMut, State},
+    text::{Font, Text},
+};
+
+use crate::{
+    components::{
+        note::{NoteReader, NoteText, NoteTextBundle},
+        player::PlayerInventory,
+    },
+    configuration::controls::InputAction,
+    states::AppState,
+};
+
+const PADDING: f32 = 15.0;
+const FONT_SIZE: f32 = 20.0;
+
+pub fn add_note_reader(mut commands: Commands, query: Query<&NoteReader>) {
+    if !query.is_empty() {
+        return;
+    }
+
+    commands.spawn(NoteReader::default());
+}
+
+pub fn open_close_note_input_system(
+    actions: Res<Input<InputAction>>,
+    mut state: ResMut<State<AppState>>,
+    inventory_query: Query<&PlayerInventory>,
+) {
+    if state.current().ne(&AppState::InGame) && state.current().ne(&AppState::ReadingNote) {
+        return;
+    }
+
+    if !actions.just_pressed(InputAction::ReadNotes) {
+        return;
+    }
+
+    if state.current().eq(&AppState::ReadingNote) {
+        state.set(AppState::InGame).unwrap();
+        return;
+    }
+
+    if inventory_query.is_empty() {
+        return;
+    }
+
+    let inventory: &PlayerInventory = inventory_query.single();
+    if inventory.held_notes.is_empty() {
+        println!("No notes to read");
+        return;
+    }
+
+    state.set(AppState::ReadingNote).unwrap();
+}
+
+pub fn note_page_input(
+    actions: Res<Input<InputAction>>,
+    mut reader_query: Query<&mut NoteReader>,
+    inventory_query: Query<&PlayerInventory>,
+) {
+    if reader_query.is_empty() || inventory_query.is_empty() {
+        return;
+    }
+
+    let mut reader: Mut<NoteReader> = reader_query.single_mut();
+    let inventory: &PlayerInventory = inventory_query.single();
+    let note_count = inventory.held_notes.len();
+
+    if note_count == 0 {
+        return;
+    }
+
+    if actions.just_pressed(InputAction::NextPage) {
+        reader.index = (reader.index + 1) % note_count;
+    }
+
+    if actions.just_pressed(InputAction::PreviousPage) {
+        reader.index = (reader.index + note_count - 1) % note_count;
+    }
+}
+
+pub fn add_note_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font: Handle<Font> = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands.spawn(NoteTextBundle::build(
+        PADDING,
+        String::new(),
+        &font,
+        FONT_SIZE,
+    ));
+}
+
+pub fn update_note_text(
+    reader_query: Query<&NoteReader>,
+    inventory_query: Query<&PlayerInventory>,
+    mut text_query: Query<(&NoteText, &mut Text)>,
+) {
+    if reader_query.is_empty() || inventory_query.is_empty() {
+        return;
+    }
+
+    let reader: &NoteReader = reader_query.single();
+    let inventory: &PlayerInventory = inventory_query.single();
+
+    let note = match inventory.held_notes.get(reader.index) {
+        Some(it) => it,
+        _ => return,
+    };
+
+    for text_data in text_query.iter_mut() {
+        let (_, mut text): (&NoteText, Mut<Text>) = text_data;
+        let section = match text.sections.get_mut(0) {
+            Some(it) => it,
+            _ => continue,
+        };
+
+        section.value = format!(
+            "{}\n\n{}\n\n{} / {}",
+            note.title,
+            note.text,
+            reader.index + 1,
+            inventory.held_notes.len()
+        );
+    }
+}
+
+pub fn remove_note_text(mut commands: Commands, query: Query<(&NoteText, Entity)>) {
+    for data in query.iter() {
+        let (_, entity): (&NoteText, Entity) = data;
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use bevy::prelude::{
+    Added, AnyOf, Entity, Or, Query, RemovedComponents, Res, ResMut, Resource, Transform,
+};
+use tdlg::map::cells::Coordinate;
+
+use crate::{
+    components::{crop::Crop, ground::GroundTile, item::Item, structure::Structure},
+    configuration::{game::GameConfiguration, map::grid_coordinate_from_world},
+};
+
+#[derive(Default)]
+struct TileContents {
+    ground: Vec<Entity>,
+    structures: Vec<Entity>,
+    items: Vec<Entity>,
+    crops: Vec<Entity>,
+}
+
+// Which ground tiles, structures, items and crops sit on each tile of the current floor
+#[derive(Default, Resource)]
+pub struct TileOccupancy {
+    tiles: HashMap<Coordinate, TileContents>,
+    positions: HashMap<Entity, Coordinate>,
+}
+
+impl TileOccupancy {
+    pub fn ground_at(&self, coordinate: &Coordinate) -> &[Entity] {
+        self.tiles
+            .get(coordinate)
+            .map_or(&[], |contents| &contents.ground)
+    }
+
+    // Floors stack on some tiles, the one drawn on top is the ground that counts
+    pub fn ground_on_top<'a>(
+        &self,
+        coordinate: &Coordinate,
+        ground_query: &'a Query<(&GroundTile, &Transform)>,
+    ) -> Option<&'a GroundTile> {
+        self.ground_at(coordinate)
+            .iter()
+            .filter_map(|entity| ground_query.get(*entity).ok())
+            .max_by(|(_, a), (_, b)| a.translation.z.total_cmp(&b.translation.z))
+            .map(|(ground, _)| ground)
+    }
+
+    pub fn structures_at(&self, coordinate: &Coordinate) -> &[Entity] {
+        self.tiles
+            .get(coordinate)
+            .map_or(&[], |contents| &contents.structures)
+    }
+
+    pub fn items_at(&self, coordinate: &Coordinate) -> &[Entity] {
+        self.tiles
+            .get(coordinate)
+            .map_or(&[], |contents| &contents.items)
+    }
+
+    pub fn crops_at(&self, coordinate: &Coordinate) -> &[Entity] {
+        self.tiles
+            .get(coordinate)
+            .map_or(&[], |contents| &contents.crops)
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        let coordinate = match self.positions.remove(&entity) {
+            Some(it) => it,
+            _ => return,
+        };
+
+        if let Some(contents) = self.tiles.get_mut(&coordinate) {
+            contents.ground.retain(|it| *it != entity);
+            contents.structures.retain(|it| *it != entity);
+            contents.items.retain(|it| *it != entity);
+            contents.crops.retain(|it| *it != entity);
+        }
+    }
+}
+
+type AddedOccupantQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        AnyOf<(
+            &'static GroundTile,
+            &'static Structure,
+            &'static Item,
+            &'static Crop,
+        )>,
+    ),
+    Or<(
+        Added<GroundTile>,
+        Added<Structure>,
+        Added<Item>,
+        Added<Crop>,
+    )>,
+>;
+
+// Runs after the frame's commands are applied so despawns are seen before they are cleared
+pub fn update_tile_occupancy(
+    mut occupancy: ResMut<TileOccupancy>,
+    added_query: AddedOccupantQuery,
+    removed_structures: RemovedComponents<Structure>,
+    removed_items: RemovedComponents<Item>,
+    removed_crops: RemovedComponents<Crop>,
+    removed_ground: RemovedComponents<GroundTile>,
+    game_config: Res<GameConfiguration>,
+) {
+    for entity in removed_structures
+        .iter()
+        .chain(removed_items.iter())
+        .chain(removed_crops.iter())
+        .chain(removed_ground.iter())
+    {
+        occupancy.remove(entity);
+    }
+
+    for (entity, transform, (ground, structure, item, _)) in added_query.iter() {
+        let coordinate = grid_coordinate_from_world(
+            &transform.translation.truncate(),
+            game_config.map_size(),
+            game_config.tile_size(),
+        );
+
+        occupancy.remove(entity);
+        occupancy.positions.insert(entity, coordinate);
+
+        let contents = occupancy.tiles.entry(coordinate).or_default();
+        if ground.is_some() {
+            contents.ground.push(entity);
+        } else if structure.is_some() {
+            contents.structures.push(entity);
+        } else if item.is_some() {
+            contents.items.push(entity);
+        } else {
+            contents.crops.push(entity);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::{
+    input::Input,
+    math::Vec2,
+    prelude::{EventReader, Query, Res, Transform},
+    window::WindowCloseRequested,
+};
+
+use crate::{
+    components::player::{Player, PlayerInventory},
+    configuration::{controls::InputAction, game::GameConfiguration},
+    rng::GameRng,
+    save::{FloorSave, PlayerSave, SaveGame, StackSave, VisitedFloors, SAVE_PATH},
+    systems::floors::FloorContents,
+};
+
+pub fn save_game_system(
+    actions: Res<Input<InputAction>>,
+    mut close_requests: EventReader<WindowCloseRequested>,
+    player_query: Query<(&Player, &Transform, &PlayerInventory)>,
+    floor_contents: FloorContents,
+    visited_floors: Res<VisitedFloors>,
+    game_config: Res<GameConfiguration>,
+    game_rng: Res<GameRng>,
+) {
+    let close_requested = close_requests.iter().count() > 0;
+    if !actions.just_pressed(InputAction::Save) && !close_requested {
+        return;
+    }
+
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (_, player_transform, inventory): (&Player, &Transform, &PlayerInventory) =
+        player_query.single();
+
+    let player = PlayerSave {
+        position: Vec2::new(
+            player_transform.translation.x,
+            player_transform.translation.y,
+        ),
+        held_seeds: inventory
+            .held_seeds
+            .iter()
+            .map(|seeds| StackSave {
+                key: seeds.config.key.clone(),
+                quantity: seeds.quantity,
+            })
+            .collect(),
+        held_tools: inventory
+            .held_tools
+            .iter()
+            .map(|config| config.key().clone())
+            .collect(),
+        current_crop: inventory
+            .current_crop_config
+            .as_ref()
+            .map(|config| config.key.clone()),
+        current_tool: inventory.current_tool.as_ref().map(|tool| tool.key.clone()),
+        current_selected_index: inventory.current_selected_index,
+        produce: inventory
+            .held_produce
+            .iter()
+            .map(|produce| StackSave {
+                key: produce.key.clone(),
+                quantity: produce.quantity,
+            })
+            .collect(),
+        held_notes: inventory
+            .held_notes
+            .iter()
+            .map(|note| note.key.clone())
+            .collect(),
+        held_items: inventory
+            .held_items
+            .iter()
+            .map(|stack| StackSave {
+                key: stack.config.key().clone(),
+                quantity: stack.quantity,
+            })
+            .collect(),
+    };
+
+    let level = game_config.level();
+    let floor = floor_contents.capture(level, player.position);
+
+    let mut visited_floors: Vec<FloorSave> = visited_floors.floors.values().cloned().collect();
+    visited_floors.sort_by_key(|floor| floor.level);
+
+    let save = SaveGame {
+        seed: game_config.seed.clone(),
+        level,
+        ticks: floor_contents.ticks(),
+        player,
+        floor,
+        visited_floors,
+        rng: game_rng.positions(),
+    };
+
+    match save.write(SAVE_PATH) {
+        Ok(_) => println!("Saved game to {SAVE_PATH}"),
+        Err(e) => println!("Failed to save game: {e}"),
+    }
+}
@@ -1,29 +1,40 @@
+use std::collections::HashMap;
+
 use bevy::{
-    math::Vec3,
-    prelude::{Commands, Entity, Mut, Query, Res, ResMut, Transform},
+    math::{Vec2, Vec3},
+    prelude::{Commands, Mut, Query, Res, ResMut, Transform},
 };
-use tdlg::map::cells::LayerType;
+use tdlg::map::cells::Coordinate;
 
 use crate::{
     components::{
-        action::{CurrentAction, InteractAction},
-        body::Body,
-        crop::CropBundle,
+        crop::{CropBundle, CropSpawn},
+        floor::FloorLevel,
         ground::GroundTileBundle,
-        item::{ItemBundle, ItemType},
+        item::{ItemBundle, ItemStack, ItemType},
+        moisture::SoilMoisture,
         player::{Player, PlayerBundle},
-        spawns::{MapSpawn, Spawns},
+        spawns::Spawns,
         structure::StructureBundle,
-        tool::ToolType,
     },
-    configuration::{game::GameConfiguration, map::world_coordinate_from_grid},
+    configuration::{
+        floors::SoilConfig,
+        game::GameConfiguration,
+        items::ItemConfiguration,
+        layers::{LayerContent, LayerPicker, MapLayer},
+        map::{grid_coordinate_from_world, world_coordinate_from_grid},
+    },
+    rng::{floor_stream, GameRng, RngStream},
+    save::FloorSave,
     sprites::Sprites,
+    systems::map_view::FloorLayout,
 };
 
 pub fn spawn_crops(
     mut commands: Commands,
     sprites: Res<Sprites>,
     game_config: Res<GameConfiguration>,
+    mut game_rng: ResMut<GameRng>,
     query: Query<&Spawns>,
 ) {
     if query.is_empty() {
@@ -37,13 +48,16 @@ pub fn spawn_crops(
     }
 
     for spawn in spawns.crops.iter() {
-        commands.spawn(CropBundle::build(
-            spawn,
-            &sprites,
-            &spawn.config,
-            game_config.sprite_config.crop_scale,
-            game_config.sprite_config.scale,
-        ));
+        commands
+            .spawn(CropBundle::build(
+                spawn,
+                &sprites,
+                &spawn.config,
+                game_config.sprite_config.crop_scale,
+                game_config.sprite_config.scale,
+                game_rng.stream(&game_config, RngStream::Crops),
+            ))
+            .insert(FloorLevel(game_config.level()));
     }
 }
 
@@ -72,13 +86,14 @@ pub fn spawn_structures(
             _ => continue,
         };
 
-        commands.spawn(StructureBundle::build(
-            spawn.position,
-            &sprites.atlas_handle,
-            structure_config,
-            &game_config.sprite_config,
-            game_config.tile_size(),
-        ));
+        commands
+            .spawn(StructureBundle::build(
+                spawn.position,
+                &sprites.atlas_handle,
+                structure_config,
+                &game_config.sprite_config,
+            ))
+            .insert(FloorLevel(game_config.level()));
     }
 }
 
@@ -102,37 +117,6 @@ pub fn reset_structure_spawns(mut query: Query<&mut Spawns>) {
     spawns.structures.clear();
 }
 
-pub fn drop_floor(
-    mut commands: Commands,
-    mut player_query: Query<(&Player, &CurrentAction)>,
-    structure_query: Query<(&Body, Entity)>,
-    mut game_config: ResMut<GameConfiguration>,
-    mut spawns_query: Query<&mut Spawns>,
-) {
-    if player_query.is_empty() {
-        return;
-    }
-
-    if spawns_query.is_empty() {
-        return;
-    }
-
-    let (_, action): (&Player, &CurrentAction) = player_query.single_mut();
-
-    if let Some(InteractAction::DropFloors) = action.interact {
-        let world = game_config.generator(true).generate_top_down_map().unwrap();
-
-        for structure_data in structure_query.iter() {
-            let (_, entity): (&Body, Entity) = structure_data;
-
-            commands.entity(entity).despawn();
-        }
-
-        let mut spawns: Mut<Spawns> = spawns_query.single_mut();
-        spawns.map_spawn = Some(MapSpawn { map: world });
-    }
-}
-
 pub fn reset_spawn_map(mut query: Query<&mut Spawns>) {
     if query.is_empty() {
         return;
@@ -148,6 +132,8 @@ pub fn spawn_map(
     mut player_query: Query<(&Player, &mut Transform)>,
     sprites: Res<Sprites>,
     game_config: Res<GameConfiguration>,
+    mut game_rng: ResMut<GameRng>,
+    mut floor_layout: ResMut<FloorLayout>,
 ) {
     if query.is_empty() {
         return;
@@ -160,14 +146,19 @@ pub fn spawn_map(
         _ => return,
     };
 
-    let player_spawn = map_spawn.map.entry();
-    let coordinate = world_coordinate_from_grid(
-        player_spawn,
-        game_config.world_config.world_stats.map_size,
-        game_config.tile_size(),
-    );
+    let coordinate = match &map_spawn.restore {
+        Some(floor) => floor.position,
+        _ => world_coordinate_from_grid(
+            map_spawn.map.entry(),
+            game_config.map_size(),
+            game_config.tile_size(),
+        ),
+    };
     if player_query.is_empty() {
-        let player_bundle = PlayerBundle::build_main_player(coordinate, &sprites, &game_config);
+        let mut player_bundle = PlayerBundle::build_main_player(coordinate, &sprites, &game_config);
+        if let Some(player) = &map_spawn.player {
+            player.restore_inventory(&mut player_bundle.inventory, &game_config);
+        }
         commands.spawn(player_bundle);
     } else {
         let (_, mut transform): (&Player, Mut<Transform>) = player_query.single_mut();
@@ -175,168 +166,343 @@ pub fn spawn_map(
         transform.translation.y = coordinate.y;
     }
 
-    let mut spawned_shovel = false;
+    *floor_layout = FloorLayout::from_map(&map_spawn.map, game_config.map_size());
+
+    // Spawn streams start over on every map spawn, so a restored floor is laid out the same way
+    let floor_seed = game_config.floor_seed();
+    let mut layout_rng = floor_stream(&floor_seed, RngStream::Layout);
+    let mut floor_sprite_rng = floor_stream(&floor_seed, RngStream::FloorSprites);
+    let mut item_rng = floor_stream(&floor_seed, RngStream::Items);
+    let mut note_rng = floor_stream(&floor_seed, RngStream::Notes);
+    let mut layer_picker = LayerPicker::default();
+    let depth = game_config.depth();
+    let floor_level = FloorLevel(game_config.level());
+    let structure_health = game_config.world_stats().structure_health;
+    let mut soils: HashMap<Coordinate, SoilConfig> = HashMap::new();
     for cell in map_spawn.map.grid().cells() {
         for (index, layer) in cell.layers().iter().enumerate() {
+            let mapping = match MapLayer::from_layer_type(layer)
+                .and_then(|map_layer| game_config.world_config.mapping(depth, map_layer))
+            {
+                Some(it) => it,
+                _ => continue,
+            };
+
             let coordinate = world_coordinate_from_grid(
                 cell.coordinate(),
                 game_config.map_size(),
                 game_config.tile_size(),
             );
             let position = Vec3::new(coordinate.x, coordinate.y, index as f32);
-            match *layer {
-                LayerType::Floor => {
-                    let floor_config = game_config
-                        .floors_config
-                        .config_by_key("cave_floor")
-                        .unwrap();
-                    commands.spawn(GroundTileBundle::build(
-                        position,
-                        &sprites,
-                        floor_config,
-                        game_config.sprite_config.scale,
-                        game_config.tile_size(),
-                    ));
-                }
-                LayerType::RoomWall => {
-                    let structure_config = game_config
-                        .structures_config
-                        .config_by_key("room_wall")
-                        .unwrap();
-                    commands.spawn(StructureBundle::build(
-                        position,
-                        &sprites.atlas_handle,
-                        structure_config,
-                        &game_config.sprite_config,
-                        game_config.tile_size(),
-                    ));
-                }
-                LayerType::RoomFloor => {
-                    let config = game_config
-                        .floors_config
-                        .config_by_key("room_floor")
-                        .unwrap();
-                    commands.spawn(GroundTileBundle::build(
-                        position,
-                        &sprites,
-                        config,
-                        game_config.sprite_config.scale,
-                        game_config.tile_size(),
-                    ));
-                }
-                LayerType::Door => {
-                    let config = game_config
-                        .floors_config
-                        .config_by_key("room_floor")
-                        .unwrap();
-                    commands.spawn(GroundTileBundle::build(
-                        position,
-                        &sprites,
-                        config,
-                        game_config.sprite_config.scale,
-                        game_config.tile_size(),
-                    ));
+            let underground = cell.is_layer_underground(layer).unwrap_or(false);
+
+            // Guaranteed items have to be reachable, so they never spawn underground
+            let content = match layer_picker.pick(mapping, !underground, &mut layout_rng) {
+                Some(it) => it,
+                _ => continue,
+            };
+
+            // Restored floors only take their ground from the map, everything else is in the save
+            if map_spawn.restore.is_some() && !matches!(content, LayerContent::Floor(_)) {
+                continue;
+            }
+
+            match content {
+                LayerContent::Floor(key) => {
+                    if let Some(floor_config) = game_config.floors_config.config_by_key(key) {
+                        soils.insert(*cell.coordinate(), floor_config.soil);
+                        commands
+                            .spawn(GroundTileBundle::build(
+                                position,
+                                &sprites,
+                                floor_config,
+                                game_config.sprite_config.scale,
+                                &mut floor_sprite_rng,
+                            ))
+                            .insert(floor_level);
+                    }
                 }
-                LayerType::OuterWall => {
-                    let structure_config = game_config
-                        .structures_config
-                        .config_by_key("outer_wall")
-                        .unwrap();
-                    commands.spawn(StructureBundle::build(
-                        position,
-                        &sprites.atlas_handle,
-                        structure_config,
-                        &game_config.sprite_config,
-                        game_config.tile_size(),
-                    ));
+                LayerContent::Structure(key) => {
+                    if let Some(structure_config) = game_config.structures_config.config_by_key(key)
+                    {
+                        commands
+                            .spawn(
+                                StructureBundle::build(
+                                    position,
+                                    &sprites.atlas_handle,
+                                    structure_config,
+                                    &game_config.sprite_config,
+                                )
+                                .with_health_scale(structure_health),
+                            )
+                            .insert(floor_level);
+                    }
                 }
-                LayerType::Rubble => {
-                    let structure_config = game_config
-                        .structures_config
-                        .config_by_key("rubble")
-                        .unwrap();
-                    commands.spawn(StructureBundle::build(
-                        position,
-                        &sprites.atlas_handle,
-                        structure_config,
-                        &game_config.sprite_config,
-                        game_config.tile_size(),
-                    ));
+                LayerContent::Tool(key) => {
+                    if let Some(tool) = game_config.tool_configs.tool_by_key(key) {
+                        commands
+                            .spawn(ItemBundle::build(
+                                position,
+                                &sprites,
+                                tool.sprite_index.unwrap(),
+                                game_config.sprite_config.scale,
+                                underground,
+                                ItemType::Tool(tool),
+                            ))
+                            .insert(floor_level);
+                    }
                 }
-                LayerType::Table => {
-                    let structure_config = game_config
-                        .structures_config
-                        .config_by_key("table")
-                        .unwrap();
-                    commands.spawn(StructureBundle::build(
-                        position,
-                        &sprites.atlas_handle,
-                        structure_config,
-                        &game_config.sprite_config,
-                        game_config.tile_size(),
-                    ));
+                LayerContent::Item(key) => {
+                    if let Some(config) = game_config.items_config.config_by_key(key) {
+                        commands
+                            .spawn(item_stack_bundle(
+                                position,
+                                config,
+                                underground,
+                                &sprites,
+                                &game_config,
+                            ))
+                            .insert(floor_level);
+                    }
                 }
-                LayerType::Note => {
-                    println!("Note {} {:?}", index, &cell.coordinate());
+                LayerContent::RandomItem => {
+                    if let Some(config) = game_config.items_config.random_by_rarity(&mut item_rng) {
+                        commands
+                            .spawn(item_stack_bundle(
+                                position,
+                                config,
+                                underground,
+                                &sprites,
+                                &game_config,
+                            ))
+                            .insert(floor_level);
+                    }
                 }
-                LayerType::CommonItem => {
-                    println!("common item {} {:?}", index, &cell.coordinate());
-
-                    let underground = cell.is_layer_underground(layer).unwrap_or(false);
-                    if !underground {
-                        if spawned_shovel {
-                            println!("spawned hoe {:?}", &cell.coordinate());
-                            if let Some(tool) = game_config.tool_configs.tool_by_type(ToolType::Hoe)
-                            {
-                                let tool_bundle = ItemBundle::build(
-                                    position,
-                                    &sprites,
-                                    tool.sprite_index.unwrap(),
-                                    game_config.sprite_config.scale,
-                                    game_config.tile_size(),
-                                    underground,
-                                    ItemType::Tool(tool),
-                                );
-                                commands.spawn(tool_bundle);
-                            }
-                        } else if let Some(tool) =
-                            game_config.tool_configs.tool_by_type(ToolType::Shovel)
-                        {
-                            println!("spawned shovel {:?}", &cell.coordinate());
-                            let tool_bundle = ItemBundle::build(
+                LayerContent::Note => {
+                    if let Some(note) = game_config.notes_config.pick(&mut note_rng) {
+                        commands
+                            .spawn(ItemBundle::build(
                                 position,
                                 &sprites,
-                                tool.sprite_index.unwrap(),
+                                note.sprite_index.unwrap(),
                                 game_config.sprite_config.scale,
-                                game_config.tile_size(),
                                 underground,
-                                ItemType::Tool(tool),
-                            );
-                            commands.spawn(tool_bundle);
-                            spawned_shovel = true;
-                        }
+                                ItemType::Note(note.clone()),
+                            ))
+                            .insert(floor_level);
                     }
                 }
-                _ => {}
             }
         }
     }
 
+    if let Some(floor) = &map_spawn.restore {
+        spawn_saved_entities(
+            &mut commands,
+            floor,
+            map_spawn.elapsed_ticks,
+            &soils,
+            &sprites,
+            &game_config,
+            &mut game_rng,
+        );
+        return;
+    }
+
     let exit_coordinate = world_coordinate_from_grid(
         map_spawn.map.exit(),
-        game_config.world_config.world_stats.map_size,
+        game_config.map_size(),
         game_config.tile_size(),
     );
 
     println!("{:?}", map_spawn.map.exit());
 
-    let structure_config = game_config.structures_config.config_by_key("exit").unwrap();
+    // Both are required structures, validation reports a config that leaves them out
+    let structure_config = match game_config.structures_config.config_by_key("exit") {
+        Some(it) => it,
+        _ => return,
+    };
     let position = Vec3::new(exit_coordinate.x, exit_coordinate.y, 2.0);
-    commands.spawn(StructureBundle::build(
-        position,
-        &sprites.atlas_handle,
-        structure_config,
-        &game_config.sprite_config,
+    commands
+        .spawn(StructureBundle::build(
+            position,
+            &sprites.atlas_handle,
+            structure_config,
+            &game_config.sprite_config,
+        ))
+        .insert(floor_level);
+
+    if game_config.level() == 0 {
+        return;
+    }
+
+    let structure_config = match game_config.structures_config.config_by_key("ladder") {
+        Some(it) => it,
+        _ => return,
+    };
+    let entry_coordinate = world_coordinate_from_grid(
+        map_spawn.map.entry(),
+        game_config.map_size(),
         game_config.tile_size(),
-    ));
+    );
+    let position = Vec3::new(entry_coordinate.x, entry_coordinate.y, 2.0);
+    commands
+        .spawn(StructureBundle::build(
+            position,
+            &sprites.atlas_handle,
+            structure_config,
+            &game_config.sprite_config,
+        ))
+        .insert(floor_level);
+}
+
+fn item_stack_bundle(
+    position: Vec3,
+    config: &ItemConfiguration,
+    underground: bool,
+    sprites: &Sprites,
+    game_config: &GameConfiguration,
+) -> ItemBundle {
+    ItemBundle::build(
+        position,
+        sprites,
+        config.sprite_index.unwrap(),
+        game_config.sprite_config.scale,
+        underground,
+        ItemType::Stack(ItemStack {
+            config: config.clone(),
+            quantity: 1,
+        }),
+    )
+}
+
+fn spawn_saved_entities(
+    commands: &mut Commands,
+    floor: &FloorSave,
+    elapsed_ticks: u64,
+    soils: &HashMap<Coordinate, SoilConfig>,
+    sprites: &Sprites,
+    game_config: &GameConfiguration,
+    game_rng: &mut GameRng,
+) {
+    let grid_coordinate = |position: Vec2| {
+        grid_coordinate_from_world(&position, game_config.map_size(), game_config.tile_size())
+    };
+    let soil_at = |coordinate: &Coordinate| soils.get(coordinate).copied().unwrap_or_default();
+
+    for structure in floor.structures.iter() {
+        let structure_config = match game_config.structures_config.config_by_key(&structure.key) {
+            Some(it) => it,
+            _ => continue,
+        };
+
+        let mut entity = commands.spawn(
+            structure.apply(
+                StructureBundle::build(
+                    structure.position,
+                    &sprites.atlas_handle,
+                    structure_config,
+                    &game_config.sprite_config,
+                )
+                .with_health_scale(game_config.world_stats().structure_health),
+            ),
+        );
+        entity.insert(FloorLevel(game_config.level()));
+
+        if let Some(level) = structure.moisture {
+            let soil = soil_at(&grid_coordinate(structure.position.truncate()));
+            let mut moisture = SoilMoisture { level };
+            moisture.drain(elapsed_ticks, soil.drainage);
+            entity.insert(moisture);
+        }
+    }
+
+    for crop in floor.crops.iter() {
+        let crop_config = match game_config.crops_config.config_by_key(&crop.key) {
+            Some(it) => it,
+            _ => continue,
+        };
+
+        let mut crop_bundle = CropBundle::build(
+            &CropSpawn {
+                location: crop.position,
+                config: crop_config.clone(),
+            },
+            sprites,
+            crop_config,
+            game_config.sprite_config.crop_scale,
+            game_config.sprite_config.scale,
+            game_rng.stream(game_config, RngStream::Crops),
+        );
+        crop.apply(&mut crop_bundle);
+
+        // The soil under the crop dries out over the same ticks it catches up on
+        let coordinate = grid_coordinate(crop.position);
+        let moisture = floor
+            .structures
+            .iter()
+            .filter(|structure| grid_coordinate(structure.position.truncate()) == coordinate)
+            .find_map(|structure| structure.moisture)
+            .map(|level| SoilMoisture { level })
+            .unwrap_or_default();
+        crop_bundle.catch_up(
+            elapsed_ticks,
+            moisture,
+            &soil_at(&coordinate),
+            game_rng.stream(game_config, RngStream::Crops),
+        );
+
+        if crop_bundle.crop.is_dead() {
+            if let Some(structure_config) =
+                game_config.structures_config.config_by_key("dead_plant")
+            {
+                let position = Vec3::new(crop.position.x, crop.position.y, 2.0);
+                commands
+                    .spawn(StructureBundle::build(
+                        position,
+                        &sprites.atlas_handle,
+                        structure_config,
+                        &game_config.sprite_config,
+                    ))
+                    .insert(FloorLevel(game_config.level()));
+            }
+            continue;
+        }
+
+        commands
+            .spawn(crop_bundle)
+            .insert(FloorLevel(game_config.level()));
+    }
+
+    for item in floor.items.iter() {
+        let item_type = match game_config.tool_configs.tool_by_key(&item.key) {
+            Some(tool) => ItemType::Tool(tool),
+            _ => match game_config.items_config.config_by_key(&item.key) {
+                Some(config) => ItemType::Stack(ItemStack {
+                    config: config.clone(),
+                    quantity: item.quantity,
+                }),
+                _ => match game_config.notes_config.config_by_key(&item.key) {
+                    Some(note) => ItemType::Note(note.clone()),
+                    _ => continue,
+                },
+            },
+        };
+        let sprite_index = match &item_type {
+            ItemType::Tool(tool) => tool.sprite_index.unwrap(),
+            ItemType::Stack(stack) => stack.config.sprite_index.unwrap(),
+            ItemType::Note(note) => note.sprite_index.unwrap(),
+        };
+
+        commands
+            .spawn(ItemBundle::build(
+                item.position,
+                sprites,
+                sprite_index,
+                game_config.sprite_config.scale,
+                item.underground,
+                item_type,
+            ))
+            .insert(FloorLevel(game_config.level()));
+    }
 }
@@ -43,29 +43,62 @@ pub fn load_sprites(
 
     let texture_atlas = texture_atlas_builder.finish(&mut textures).unwrap();
 
+    resolve_sprite_indices(&mut game_config, &texture_atlas, &asset_server);
+
+    let atlas_handle = texture_atlases.add(texture_atlas);
+    sprites.atlas_handle = atlas_handle;
+
+    load_state.textures_set = true;
+}
+
+pub fn resolve_sprite_indices(
+    game_config: &mut GameConfiguration,
+    texture_atlas: &TextureAtlas,
+    asset_server: &AssetServer,
+) -> Vec<String> {
+    assign_sprite_indices(game_config, |sprite_location| {
+        texture_atlas.get_texture_index(&asset_server.get_handle(sprite_location))
+    })
+}
+
+pub fn assign_sprite_indices(
+    game_config: &mut GameConfiguration,
+    mut index_of: impl FnMut(&str) -> Option<usize>,
+) -> Vec<String> {
+    let mut unresolved = Vec::new();
+
     for config in game_config.crops_config.configurations.as_mut_slice() {
         for mut stage in config.stages.as_mut_slice() {
-            let handle = asset_server.get_handle(stage.sprite_location());
-            if let Some(index) = texture_atlas.get_texture_index(&handle) {
+            if let Some(index) = index_of(stage.sprite_location()) {
                 stage.sprite_index = Some(index);
+            } else {
+                unresolved.push(stage.sprite_location().to_string());
             }
         }
+
+        if let Some(index) = index_of(config.withered.sprite_location()) {
+            config.withered.sprite_index = Some(index);
+        } else {
+            unresolved.push(config.withered.sprite_location().to_string());
+        }
     }
 
     for config in game_config.structures_config.configurations.as_mut_slice() {
         for mut structure_health in config.health_configs.as_mut_slice() {
-            let handle = asset_server.get_handle(structure_health.sprite_location());
-            if let Some(index) = texture_atlas.get_texture_index(&handle) {
+            if let Some(index) = index_of(structure_health.sprite_location()) {
                 structure_health.sprite_index = Some(index);
+            } else {
+                unresolved.push(structure_health.sprite_location().to_string());
             }
         }
     }
 
     for config in game_config.floors_config.configurations.as_mut_slice() {
         for mut sprite_options in config.sprite_options.as_mut_slice() {
-            let handle = asset_server.get_handle(sprite_options.sprite_location());
-            if let Some(index) = texture_atlas.get_texture_index(&handle) {
+            if let Some(index) = index_of(sprite_options.sprite_location()) {
                 sprite_options.sprite_index = Some(index);
+            } else {
+                unresolved.push(sprite_options.sprite_location().to_string());
             }
         }
     }
@@ -76,21 +109,36 @@ pub fn load_sprites(
         .options
         .as_mut_slice()
     {
-        let handle = asset_server.get_handle(config.sprite_location());
-        if let Some(index) = texture_atlas.get_texture_index(&handle) {
+        if let Some(index) = index_of(config.sprite_location()) {
             config.sprite_index = Some(index);
+        } else {
+            unresolved.push(config.sprite_location().to_string());
         }
     }
 
     for config in game_config.tool_configs.configurations.as_mut_slice() {
-        let handle = asset_server.get_handle(config.sprite_location());
-        if let Some(index) = texture_atlas.get_texture_index(&handle) {
+        if let Some(index) = index_of(config.sprite_location()) {
             config.sprite_index = Some(index);
+        } else {
+            unresolved.push(config.sprite_location().to_string());
         }
     }
 
-    let atlas_handle = texture_atlases.add(texture_atlas);
-    sprites.atlas_handle = atlas_handle;
+    for config in game_config.notes_config.configurations.as_mut_slice() {
+        if let Some(index) = index_of(config.sprite_location()) {
+            config.sprite_index = Some(index);
+        } else {
+            unresolved.push(config.sprite_location().to_string());
+        }
+    }
 
-    load_state.textures_set = true;
+    for config in game_config.items_config.configurations.as_mut_slice() {
+        if let Some(index) = index_of(config.sprite_location()) {
+            config.sprite_index = Some(index);
+        } else {
+            unresolved.push(config.sprite_location().to_string());
+        }
+    }
+
+    unresolved
 }
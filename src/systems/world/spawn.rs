@@ -2,12 +2,14 @@ use bevy::prelude::{Commands, Query, Res};
 
 use crate::{
     configuration::game::GameConfiguration, components::world::{WorldTickTimer, World},
+    save::LoadedSave,
 };
 
 pub fn spawn(
     mut commands: Commands,
     query: Query<&WorldTickTimer>,
     game_config: Res<GameConfiguration>,
+    loaded_save: Res<LoadedSave>,
 ) {
     if !query.is_empty() {
         return;
@@ -16,5 +18,10 @@ pub fn spawn(
     let world_timer = WorldTickTimer(game_config.world_tick_timer());
     commands.spawn(world_timer);
 
-    commands.spawn(World::default());
+    // Saved floors remember the tick they were left on, so the count carries over from the save
+    let ticks = loaded_save.0.as_ref().map_or(0, |save| save.ticks);
+    commands.spawn(World {
+        ticks,
+        ..Default::default()
+    });
 }
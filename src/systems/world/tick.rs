@@ -28,4 +28,7 @@ pub fn check_world_actions(
     let mut world: Mut<World> = world_query.single_mut();
 
     world.tick_just_finished = timer.0.just_finished();
+    if world.tick_just_finished {
+        world.ticks += 1;
+    }
 }